@@ -10,6 +10,11 @@ const server = engine.attach(http, {
 });
 
 console.log("Started")
+server.on('initial_headers', (headers, req) => {
+    // exposed to the client through `handshake_headers`
+    headers['X-Handshake-Test'] = 'engine.io';
+});
+
 server.on('connection', socket => {
     console.log("Connected");
 
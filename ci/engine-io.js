@@ -5,9 +5,11 @@ const engine = require('engine.io');
 const http = require('http').createServer().listen(4201);
 // the engine.io client runs on port 4201
 const server = engine.attach(http);
+// the same server is mounted at a custom path as well
+const customServer = engine.attach(http, { path: '/custom' });
 
 console.log("Started")
-server.on('connection', socket => {
+const onConnection = socket => {
     console.log("Connected");
 
     socket.on('message', message => {
@@ -39,4 +41,6 @@ server.on('connection', socket => {
     });
 
     socket.send('hello client');
-});
+};
+server.on('connection', onConnection);
+customServer.on('connection', onConnection);
@@ -20,6 +20,12 @@ var callback = client => {
         }
     });
 
+    client.on('typed-ack', function (arg, ack) {
+        if (ack) {
+            ack({ name: 'woot', count: 2 }, 'ignored');
+        }
+    });
+
     client.on('binary', data => {
         var bufView = new Uint8Array(data);
         console.log(['binary', 'Yehaa binary payload!']);
@@ -36,12 +42,15 @@ var callback = client => {
             ack(Buffer.from([1, 2, 3]));
         }
     });
-    client.emit('Hello from the message event!');
+    client.send('Hello from the message event!');
     client.emit('test', 'Hello from the test event!');
     client.emit(Buffer.from([4, 5, 6]));
     client.emit('test', Buffer.from([1, 2, 3]));
 };
 io.on('connection', callback);
 io.of('/admin').on('connection', callback);
+// the same server is mounted at a custom path as well
+const customIo = require('socket.io')(server, { path: '/custom' });
+customIo.on('connection', callback);
 // the socket.io client runs on port 4201
 server.listen(4200);
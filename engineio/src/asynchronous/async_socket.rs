@@ -28,7 +28,7 @@ pub struct Socket {
     on_close: OptionalCallback<()>,
     on_data: OptionalCallback<Bytes>,
     on_error: OptionalCallback<String>,
-    on_open: OptionalCallback<()>,
+    on_open: OptionalCallback<HandshakePacket>,
     on_packet: OptionalCallback<Packet>,
     connected: Arc<AtomicBool>,
     last_ping: Arc<Mutex<Instant>>,
@@ -44,7 +44,7 @@ impl Socket {
         on_close: OptionalCallback<()>,
         on_data: OptionalCallback<Bytes>,
         on_error: OptionalCallback<String>,
-        on_open: OptionalCallback<()>,
+        on_open: OptionalCallback<HandshakePacket>,
         on_packet: OptionalCallback<Packet>,
     ) -> Self {
         Socket {
@@ -71,7 +71,8 @@ impl Socket {
 
         if let Some(on_open) = self.on_open.as_ref() {
             let on_open = on_open.clone();
-            self.handle.spawn(async move { on_open(()).await });
+            let handshake = self.connection_data.as_ref().clone();
+            self.handle.spawn(async move { on_open(handshake).await });
         }
 
         // set the last ping to now and set the connected state
@@ -198,6 +199,11 @@ impl Socket {
         Ok(self.connected.load(Ordering::Acquire))
     }
 
+    /// Returns the handshake the server answered the connection with.
+    pub(crate) fn handshake(&self) -> &HandshakePacket {
+        &self.connection_data
+    }
+
     pub(crate) async fn pinged(&self) {
         *self.last_ping.lock().await = Instant::now();
     }
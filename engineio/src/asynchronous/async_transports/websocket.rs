@@ -10,11 +10,11 @@ use futures_util::stream::StreamExt;
 use futures_util::Stream;
 use http::HeaderMap;
 use tokio::sync::RwLock;
-use tokio_tungstenite::connect_async;
+use tokio_tungstenite::client_async_tls_with_config;
 use tungstenite::client::IntoClientRequest;
 use url::Url;
 
-use super::websocket_general::AsyncWebsocketGeneralTransport;
+use super::websocket_general::{connect_tcp, AsyncWebsocketGeneralTransport};
 
 /// An asynchronous websocket transport type.
 /// This type only allows for plain websocket
@@ -28,6 +28,16 @@ pub struct WebsocketTransport {
 impl WebsocketTransport {
     /// Creates a new instance over a request that might hold additional headers and an URL.
     pub async fn new(base_url: Url, headers: Option<HeaderMap>) -> Result<Self> {
+        Self::with_nodelay(base_url, headers, true).await
+    }
+
+    /// Creates a new instance like [`WebsocketTransport::new`], `nodelay` sets whether
+    /// Nagle's algorithm is disabled for the connection.
+    pub(crate) async fn with_nodelay(
+        base_url: Url,
+        headers: Option<HeaderMap>,
+        nodelay: bool,
+    ) -> Result<Self> {
         let mut url = base_url;
         url.query_pairs_mut().append_pair("transport", "websocket");
         url.set_scheme("ws").unwrap();
@@ -38,7 +48,8 @@ impl WebsocketTransport {
             req.headers_mut().extend(map);
         }
 
-        let (ws_stream, _) = connect_async(req).await?;
+        let stream = connect_tcp(&url, nodelay).await?;
+        let (ws_stream, _) = client_async_tls_with_config(req, stream, None, None).await?;
         let (sen, rec) = ws_stream.split();
 
         let inner = AsyncWebsocketGeneralTransport::new(sen, rec).await;
@@ -10,6 +10,7 @@ use futures_util::{
 use tokio::{net::TcpStream, sync::Mutex};
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use tungstenite::Message;
+use url::{Host, Url};
 
 type AsyncWebsocketSender = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
 type AsyncWebsocketReceiver = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
@@ -85,13 +86,7 @@ impl AsyncWebsocketGeneralTransport {
             let next = receiver.next().await;
             match next {
                 Some(Ok(Message::Text(str))) => return Ok(Some(Bytes::from(str))),
-                Some(Ok(Message::Binary(data))) => {
-                    let mut msg = BytesMut::with_capacity(data.len() + 1);
-                    msg.put_u8(PacketId::Message as u8);
-                    msg.put(data.as_ref());
-
-                    return Ok(Some(msg.freeze()));
-                }
+                Some(Ok(Message::Binary(data))) => return Ok(Some(binary_message(&data))),
                 // ignore packets other than text and binary
                 Some(Ok(_)) => (),
                 Some(Err(err)) => return Err(err.into()),
@@ -101,6 +96,38 @@ impl AsyncWebsocketGeneralTransport {
     }
 }
 
+/// Opens the TCP connection a websocket is established over. `nodelay` disables
+/// Nagle's algorithm, as engine.io packets are mostly small and latency sensitive.
+pub(crate) async fn connect_tcp(url: &Url, nodelay: bool) -> Result<TcpStream> {
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| Error::InvalidUrlScheme(url.scheme().to_owned()))?;
+    let stream = match url.host() {
+        Some(Host::Domain(domain)) => TcpStream::connect((domain, port)).await?,
+        Some(Host::Ipv4(ip)) => TcpStream::connect((ip, port)).await?,
+        Some(Host::Ipv6(ip)) => TcpStream::connect((ip, port)).await?,
+        None => return Err(Error::InvalidUrlScheme(url.scheme().to_owned())),
+    };
+    stream.set_nodelay(nodelay)?;
+
+    Ok(stream)
+}
+
+/// Frames the data of a binary websocket message as an engine.io message packet. An
+/// empty message is framed as an empty base64 packet instead, as a bare message id
+/// would be rejected as incomplete.
+fn binary_message(data: &[u8]) -> Bytes {
+    if data.is_empty() {
+        return Bytes::from_static(b"b");
+    }
+
+    let mut msg = BytesMut::with_capacity(data.len() + 1);
+    msg.put_u8(PacketId::Message as u8);
+    msg.put(data);
+
+    msg.freeze()
+}
+
 impl Stream for AsyncWebsocketGeneralTransport {
     type Item = Result<Bytes>;
 
@@ -115,11 +142,7 @@ impl Stream for AsyncWebsocketGeneralTransport {
             match next {
                 Some(Ok(Message::Text(str))) => return Poll::Ready(Some(Ok(Bytes::from(str)))),
                 Some(Ok(Message::Binary(data))) => {
-                    let mut msg = BytesMut::with_capacity(data.len() + 1);
-                    msg.put_u8(PacketId::Message as u8);
-                    msg.put(data.as_ref());
-
-                    return Poll::Ready(Some(Ok(msg.freeze())));
+                    return Poll::Ready(Some(Ok(binary_message(&data))))
                 }
                 // ignore packets other than text and binary
                 Some(Ok(_)) => (),
@@ -129,3 +152,35 @@ impl Stream for AsyncWebsocketGeneralTransport {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connect_tcp_nodelay() -> Result<()> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = Url::parse(&format!("ws://{}/", listener.local_addr()?))?;
+
+        let stream = connect_tcp(&url, true).await?;
+        assert!(stream.nodelay()?);
+
+        let stream = connect_tcp(&url, false).await?;
+        assert!(!stream.nodelay()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_message() -> Result<()> {
+        let packet = Packet::try_from(binary_message(b"Hello"))?;
+        assert_eq!(packet.data, Bytes::from_static(b"Hello"));
+
+        // an empty attachment must still decode into an (empty) packet
+        let packet = Packet::try_from(binary_message(&[]))?;
+        assert_eq!(packet.packet_id, PacketId::MessageBinary);
+        assert!(packet.data.is_empty());
+
+        Ok(())
+    }
+}
@@ -11,12 +11,12 @@ use futures_util::StreamExt;
 use http::HeaderMap;
 use native_tls::TlsConnector;
 use tokio::sync::RwLock;
-use tokio_tungstenite::connect_async_tls_with_config;
+use tokio_tungstenite::client_async_tls_with_config;
 use tokio_tungstenite::Connector;
 use tungstenite::client::IntoClientRequest;
 use url::Url;
 
-use super::websocket_general::AsyncWebsocketGeneralTransport;
+use super::websocket_general::{connect_tcp, AsyncWebsocketGeneralTransport};
 
 /// An asynchronous websocket transport type.
 /// This type only allows for secure websocket
@@ -29,11 +29,13 @@ pub struct WebsocketSecureTransport {
 
 impl WebsocketSecureTransport {
     /// Creates a new instance over a request that might hold additional headers, a possible
-    /// Tls connector and an URL.
+    /// Tls connector and an URL. `nodelay` sets whether Nagle's algorithm is disabled for
+    /// the connection.
     pub(crate) async fn new(
         base_url: Url,
         tls_config: Option<TlsConnector>,
         headers: Option<HeaderMap>,
+        nodelay: bool,
     ) -> Result<Self> {
         let mut url = base_url;
         url.query_pairs_mut().append_pair("transport", "websocket");
@@ -45,8 +47,10 @@ impl WebsocketSecureTransport {
             req.headers_mut().extend(map);
         }
 
+        let stream = connect_tcp(&url, nodelay).await?;
         let (ws_stream, _) =
-            connect_async_tls_with_config(req, None, tls_config.map(Connector::NativeTls)).await?;
+            client_async_tls_with_config(req, stream, None, tls_config.map(Connector::NativeTls))
+                .await?;
 
         let (sen, rec) = ws_stream.split();
         let inner = AsyncWebsocketGeneralTransport::new(sen, rec).await;
@@ -131,6 +135,7 @@ mod test {
             Url::from_str(&url[..])?,
             Some(crate::test::tls_connector()?),
             None,
+            true,
         )
         .await
     }
@@ -2,7 +2,7 @@ use bytes::Bytes;
 use futures_util::future::BoxFuture;
 use std::{fmt::Debug, ops::Deref, sync::Arc};
 
-use crate::Packet;
+use crate::{packet::HandshakePacket, Packet};
 
 /// Internal type, provides a way to store futures and return them in a boxed manner.
 pub(crate) type DynAsyncCallback<I> = dyn 'static + Send + Sync + Fn(I) -> BoxFuture<'static, ()>;
@@ -56,6 +56,20 @@ impl Debug for OptionalCallback<()> {
     }
 }
 
+#[cfg_attr(tarpaulin, ignore)]
+impl Debug for OptionalCallback<HandshakePacket> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        f.write_fmt(format_args!(
+            "Callback({:?})",
+            if self.inner.is_some() {
+                "Fn(HandshakePacket)"
+            } else {
+                "None"
+            }
+        ))
+    }
+}
+
 #[cfg_attr(tarpaulin, ignore)]
 impl Debug for OptionalCallback<Packet> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
@@ -1,17 +1,25 @@
-use std::{fmt::Debug, pin::Pin};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
 
 use crate::{
     asynchronous::{async_socket::Socket as InnerSocket, generator::StreamGenerator},
-    error::Result,
+    error::{Error, Result},
+    packet::HandshakePacket,
     Packet,
 };
 use async_stream::try_stream;
-use futures_util::{Stream, StreamExt};
+use futures_util::{future::BoxFuture, ready, FutureExt, Sink, Stream, StreamExt};
 
-#[derive(Clone)]
 pub struct Client {
     pub(super) socket: InnerSocket,
     generator: StreamGenerator<Packet>,
+    // the emit started by `Sink::start_send` that still needs to be driven to completion,
+    // every clone has its own so they can't drop each other's packets
+    pending_emit: Mutex<Option<BoxFuture<'static, Result<()>>>>,
 }
 
 impl Client {
@@ -19,6 +27,7 @@ impl Client {
         Client {
             socket: socket.clone(),
             generator: StreamGenerator::new(Self::stream(socket)),
+            pending_emit: Mutex::new(None),
         }
     }
 
@@ -59,6 +68,12 @@ impl Client {
     pub fn is_connected(&self) -> Result<bool> {
         self.socket.is_connected()
     }
+
+    /// Returns the handshake the server answered the connection with, e.g. to read
+    /// its `ping_interval` or the fields in `extra`.
+    pub fn handshake(&self) -> HandshakePacket {
+        self.socket.handshake().clone()
+    }
 }
 
 impl Stream for Client {
@@ -72,6 +87,47 @@ impl Stream for Client {
     }
 }
 
+/// Sending a packet through the sink is equivalent to calling [`Client::emit`].
+/// Closing the sink only flushes the pending packet, the connection stays open
+/// until [`Client::disconnect`] is called.
+impl Sink<Packet> for Client {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.poll_flush(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Packet) -> Result<()> {
+        let socket = self.socket.clone();
+        *self.pending_emit.lock()? = Some(Box::pin(async move { socket.emit(item).await }));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut pending_emit = self.pending_emit.lock()?;
+        let result = match pending_emit.as_mut() {
+            Some(emit) => ready!(emit.poll_unpin(cx)),
+            None => Ok(()),
+        };
+        *pending_emit = None;
+        Poll::Ready(result)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl Clone for Client {
+    fn clone(&self) -> Self {
+        Client {
+            socket: self.socket.clone(),
+            generator: self.generator.clone(),
+            pending_emit: Mutex::new(None),
+        }
+    }
+}
+
 impl Debug for Client {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Client")
@@ -86,7 +142,7 @@ mod test {
     use super::*;
     use crate::{asynchronous::ClientBuilder, header::HeaderMap, packet::PacketId, Error};
     use bytes::Bytes;
-    use futures_util::StreamExt;
+    use futures_util::{SinkExt, StreamExt};
     use native_tls::TlsConnector;
     use url::Url;
 
@@ -173,6 +229,82 @@ mod test {
         socket.close().await
     }
 
+    #[tokio::test]
+    async fn test_stream_and_sink() -> Result<()> {
+        let url = crate::test::engine_io_server()?;
+        let mut socket = builder(url).build().await?;
+
+        socket.connect().await?;
+
+        assert_eq!(
+            socket.next().await.unwrap()?,
+            Packet::new(PacketId::Message, "hello client")
+        );
+
+        socket
+            .send(Packet::new(PacketId::Message, "respond"))
+            .await?;
+
+        assert_eq!(
+            socket.next().await.unwrap()?,
+            Packet::new(PacketId::Message, "Roger Roger")
+        );
+
+        socket.close().await?;
+
+        // sending after disconnecting surfaces the emit error through the sink
+        assert!(socket
+            .send(Packet::new(PacketId::Message, "too late"))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sink_clones() -> Result<()> {
+        let url = crate::test::engine_io_server()?;
+        let mut socket = builder(url).build().await?;
+        socket.connect().await?;
+        assert_eq!(
+            socket.next().await.unwrap()?,
+            Packet::new(PacketId::Message, "hello client")
+        );
+
+        // both clones start a send before either of them is flushed
+        let mut first = socket.clone();
+        let mut second = socket.clone();
+        futures_util::future::poll_fn(|cx| Pin::new(&mut first).poll_ready(cx)).await?;
+        futures_util::future::poll_fn(|cx| Pin::new(&mut second).poll_ready(cx)).await?;
+        Pin::new(&mut first).start_send(Packet::new(PacketId::Message, "respond"))?;
+        Pin::new(&mut second).start_send(Packet::new(PacketId::Message, "respond"))?;
+        first.flush().await?;
+        second.flush().await?;
+
+        for _ in 0..2 {
+            assert_eq!(
+                socket.next().await.unwrap()?,
+                Packet::new(PacketId::Message, "Roger Roger")
+            );
+        }
+
+        socket.close().await
+    }
+
+    #[tokio::test]
+    async fn test_handshake() -> Result<()> {
+        let url = crate::test::engine_io_server()?;
+        let socket = builder(url).build().await?;
+
+        let handshake = socket.handshake();
+        // the defaults of the engine.io test server
+        assert_eq!(handshake.ping_interval, 25000);
+        assert_eq!(handshake.ping_timeout, 20000);
+        assert!(!handshake.sid.is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_connection_long() -> Result<()> {
         // Long lived socket to receive pings
@@ -205,6 +337,14 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_connection_custom_path() -> Result<()> {
+        // the test server is mounted at `/custom/` as well
+        let url = crate::test::engine_io_server()?;
+        let socket = builder(url).path("/custom/").build().await?;
+        test_connection(socket).await
+    }
+
     #[tokio::test]
     async fn test_connection_dynamic() -> Result<()> {
         let url = crate::test::engine_io_server()?;
@@ -23,8 +23,9 @@ pub struct ClientBuilder {
     tls_config: Option<TlsConnector>,
     headers: Option<HeaderMap>,
     handshake: Option<HandshakePacket>,
+    tcp_nodelay: bool,
     on_error: OptionalCallback<String>,
-    on_open: OptionalCallback<()>,
+    on_open: OptionalCallback<HandshakePacket>,
     on_close: OptionalCallback<()>,
     on_data: OptionalCallback<Bytes>,
     on_packet: OptionalCallback<Packet>,
@@ -45,6 +46,7 @@ impl ClientBuilder {
             headers: None,
             tls_config: None,
             handshake: None,
+            tcp_nodelay: true,
             on_close: OptionalCallback::default(),
             on_data: OptionalCallback::default(),
             on_error: OptionalCallback::default(),
@@ -59,12 +61,26 @@ impl ClientBuilder {
         self
     }
 
+    /// Overrides the path the engine.io server is mounted at, e.g. `/custom/`.
+    /// Defaults to the path of the URL, or `/engine.io/` if the URL has none.
+    pub fn path<T: Into<String>>(mut self, path: T) -> Self {
+        self.url.set_path(&path.into());
+        self
+    }
+
     /// Specify transport's HTTP headers
     pub fn headers(mut self, headers: HeaderMap) -> Self {
         self.headers = Some(headers);
         self
     }
 
+    /// Whether Nagle's algorithm is disabled for websocket connections, which is the
+    /// default. Enabling it batches small packets at the cost of latency.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
     /// Registers the `on_close` callback.
     pub fn on_close<T>(mut self, callback: T) -> Self
     where
@@ -92,10 +108,11 @@ impl ClientBuilder {
         self
     }
 
-    /// Registers the `on_open` callback.
+    /// Registers the `on_open` callback, which gets the handshake the server answered
+    /// the connection with.
     pub fn on_open<T>(mut self, callback: T) -> Self
     where
-        T: 'static + Send + Sync + Fn(()) -> BoxFuture<'static, ()>,
+        T: 'static + Send + Sync + Fn(HandshakePacket) -> BoxFuture<'static, ()>,
     {
         self.on_open = OptionalCallback::new(callback);
         self
@@ -209,7 +226,9 @@ impl ClientBuilder {
 
         match self.url.scheme() {
             "http" | "ws" => {
-                let mut transport = WebsocketTransport::new(self.url.clone(), headers).await?;
+                let mut transport =
+                    WebsocketTransport::with_nodelay(self.url.clone(), headers, self.tcp_nodelay)
+                        .await?;
 
                 if self.handshake.is_some() {
                     transport.upgrade().await?;
@@ -233,6 +252,7 @@ impl ClientBuilder {
                     self.url.clone(),
                     self.tls_config.clone(),
                     headers,
+                    self.tcp_nodelay,
                 )
                 .await?;
 
@@ -1,3 +1,4 @@
+use crate::packet::HandshakePacket;
 use crate::Packet;
 use bytes::Bytes;
 use std::fmt::Debug;
@@ -57,6 +58,20 @@ impl Debug for OptionalCallback<()> {
     }
 }
 
+#[cfg_attr(tarpaulin, ignore)]
+impl Debug for OptionalCallback<HandshakePacket> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        f.write_fmt(format_args!(
+            "Callback({:?})",
+            if self.inner.is_some() {
+                "Fn(HandshakePacket)"
+            } else {
+                "None"
+            }
+        ))
+    }
+}
+
 #[cfg_attr(tarpaulin, ignore)]
 impl Debug for OptionalCallback<Packet> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
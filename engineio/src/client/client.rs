@@ -12,11 +12,14 @@ use native_tls::TlsConnector;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt::Debug;
+use std::time::Duration;
 use url::Url;
 
 #[derive(Clone, Debug)]
 pub struct Client {
     socket: InnerSocket,
+    handshake_headers: HeaderMap,
+    auto_pong: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -25,8 +28,13 @@ pub struct ClientBuilder {
     tls_config: Option<TlsConnector>,
     headers: Option<HeaderMap>,
     handshake: Option<HandshakePacket>,
+    handshake_headers: Option<HeaderMap>,
+    connect_retries: usize,
+    retry_delay: Duration,
+    auto_pong: bool,
+    tcp_nodelay: bool,
     on_error: OptionalCallback<String>,
-    on_open: OptionalCallback<()>,
+    on_open: OptionalCallback<HandshakePacket>,
     on_close: OptionalCallback<()>,
     on_data: OptionalCallback<Bytes>,
     on_packet: OptionalCallback<Packet>,
@@ -47,6 +55,11 @@ impl ClientBuilder {
             headers: None,
             tls_config: None,
             handshake: None,
+            handshake_headers: None,
+            connect_retries: 0,
+            retry_delay: Duration::ZERO,
+            auto_pong: true,
+            tcp_nodelay: true,
             on_close: OptionalCallback::default(),
             on_data: OptionalCallback::default(),
             on_error: OptionalCallback::default(),
@@ -61,12 +74,45 @@ impl ClientBuilder {
         self
     }
 
+    /// Overrides the path the engine.io server is mounted at, e.g. `/custom/`.
+    /// Defaults to the path of the URL, or `/engine.io/` if the URL has none.
+    pub fn path<T: Into<String>>(mut self, path: T) -> Self {
+        self.url.set_path(&path.into());
+        self
+    }
+
     /// Specify transport's HTTP headers
     pub fn headers(mut self, headers: HeaderMap) -> Self {
         self.headers = Some(headers);
         self
     }
 
+    /// Retries the initial handshake up to `retries` times, waiting `delay` between
+    /// the attempts, as long as the server can't be reached. Other errors, e.g. an
+    /// invalid handshake, fail right away. This covers the polling handshake as well
+    /// as opening a websocket-only connection, not reconnecting an already
+    /// established connection.
+    pub fn connect_retries(mut self, retries: usize, delay: Duration) -> Self {
+        self.connect_retries = retries;
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Whether the client answers the server's pings on its own, which is the
+    /// default. When disabled, pongs have to be sent with [`Client::pong`] or the
+    /// server closes the connection after its ping timeout.
+    pub fn auto_pong(mut self, auto_pong: bool) -> Self {
+        self.auto_pong = auto_pong;
+        self
+    }
+
+    /// Whether Nagle's algorithm is disabled for websocket connections, which is the
+    /// default. Enabling it batches small packets at the cost of latency.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
     /// Registers the `on_close` callback.
     pub fn on_close<T>(mut self, callback: T) -> Self
     where
@@ -94,10 +140,11 @@ impl ClientBuilder {
         self
     }
 
-    /// Registers the `on_open` callback.
+    /// Registers the `on_open` callback, which gets the handshake the server answered
+    /// the connection with.
     pub fn on_open<T>(mut self, callback: T) -> Self
     where
-        T: Fn(()) + 'static + Sync + Send,
+        T: Fn(HandshakePacket) + 'static + Sync + Send,
     {
         self.on_open = OptionalCallback::new(callback);
         self
@@ -119,9 +166,14 @@ impl ClientBuilder {
             return Ok(());
         }
 
+        self.handshake_from_bytes(transport.poll()?)
+    }
+
+    /// Parses the handshake packet and stores the sid in the url
+    fn handshake_from_bytes(&mut self, data: Bytes) -> Result<()> {
         let mut url = self.url.clone();
 
-        let handshake: HandshakePacket = Packet::try_from(transport.poll()?)?.try_into()?;
+        let handshake: HandshakePacket = Packet::try_from(data)?.try_into()?;
 
         // update the base_url with the new sid
         url.query_pairs_mut().append_pair("sid", &handshake.sid[..]);
@@ -145,7 +197,25 @@ impl ClientBuilder {
             self.headers.clone().map(|v| v.try_into().unwrap()),
         );
 
-        self.handshake_with_transport(&transport)
+        let (data, headers) = self.with_retries(|| transport.poll_with_headers())?;
+        self.handshake_headers = Some(headers.into());
+
+        self.handshake_from_bytes(data)
+    }
+
+    /// Runs `attempt` until it succeeds, retrying it up to `connect_retries` times as
+    /// long as the server can't be reached.
+    fn with_retries<T>(&self, mut attempt: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut retries_left = self.connect_retries;
+        loop {
+            match attempt() {
+                Err(err) if retries_left > 0 && err.is_transient() => {
+                    retries_left -= 1;
+                    std::thread::sleep(self.retry_delay);
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Build websocket if allowed, if not fall back to polling
@@ -181,6 +251,8 @@ impl ClientBuilder {
                 self.on_open,
                 self.on_packet,
             ),
+            handshake_headers: self.handshake_headers.unwrap_or_default(),
+            auto_pong: self.auto_pong,
         })
     }
 
@@ -208,7 +280,9 @@ impl ClientBuilder {
 
         match url.scheme() {
             "http" | "ws" => {
-                let transport = WebsocketTransport::new(url, headers)?;
+                let transport = self.with_retries(|| {
+                    WebsocketTransport::with_nodelay(url.clone(), headers.clone(), self.tcp_nodelay)
+                })?;
                 if self.handshake.is_some() {
                     transport.upgrade()?;
                 } else {
@@ -226,11 +300,19 @@ impl ClientBuilder {
                         self.on_open,
                         self.on_packet,
                     ),
+                    handshake_headers: self.handshake_headers.unwrap_or_default(),
+                    auto_pong: self.auto_pong,
                 })
             }
             "https" | "wss" => {
-                let transport =
-                    WebsocketSecureTransport::new(url, self.tls_config.clone(), headers)?;
+                let transport = self.with_retries(|| {
+                    WebsocketSecureTransport::with_nodelay(
+                        url.clone(),
+                        self.tls_config.clone(),
+                        headers.clone(),
+                        self.tcp_nodelay,
+                    )
+                })?;
                 if self.handshake.is_some() {
                     transport.upgrade()?;
                 } else {
@@ -248,6 +330,8 @@ impl ClientBuilder {
                         self.on_open,
                         self.on_packet,
                     ),
+                    handshake_headers: self.handshake_headers.unwrap_or_default(),
+                    auto_pong: self.auto_pong,
                 })
             }
             _ => Err(Error::InvalidUrlScheme(url.scheme().to_string())),
@@ -299,6 +383,21 @@ impl Client {
         self.socket.emit(packet)
     }
 
+    /// Writes `data` verbatim as a polling request body or a websocket text
+    /// message, bypassing the engine.io packet encoding. This is meant for
+    /// protocol debugging and fuzzing only, the server will most likely treat
+    /// anything that isn't a valid packet as an error.
+    #[cfg(feature = "raw")]
+    pub fn emit_raw(&self, data: Bytes) -> Result<()> {
+        self.socket.emit_raw(data)
+    }
+
+    /// Sends a pong packet to the server. Only needed if answering pings was
+    /// turned off via [`ClientBuilder::auto_pong`].
+    pub fn pong(&self) -> Result<()> {
+        self.emit(Packet::new(PacketId::Pong, Bytes::new()))
+    }
+
     /// Polls for next payload
     #[doc(hidden)]
     pub fn poll(&self) -> Result<Option<Packet>> {
@@ -324,7 +423,9 @@ impl Client {
                 }
                 PacketId::Ping => {
                     self.socket.pinged()?;
-                    self.emit(Packet::new(PacketId::Pong, Bytes::new()))?;
+                    if self.auto_pong {
+                        self.pong()?;
+                    }
                 }
                 PacketId::Pong => {
                     // this will never happen as the pong packet is
@@ -344,6 +445,19 @@ impl Client {
         self.socket.is_connected()
     }
 
+    /// Returns the handshake the server answered the connection with, e.g. to read
+    /// its `ping_interval` or the fields in `extra`.
+    pub fn handshake(&self) -> HandshakePacket {
+        self.socket.handshake().clone()
+    }
+
+    /// Returns the headers of the HTTP response to the polling handshake, e.g. to
+    /// read a session cookie set by the server. The map is empty if the handshake
+    /// was performed over a websocket transport. Header names are lowercase.
+    pub fn handshake_headers(&self) -> HeaderMap {
+        self.handshake_headers.clone()
+    }
+
     pub fn iter(&self) -> Iter {
         Iter { socket: self }
     }
@@ -391,6 +505,7 @@ mod test {
 
         Ok(())
     }
+    use crate::header::HeaderValue;
     use reqwest::header::HOST;
 
     use crate::packet::Packet;
@@ -462,6 +577,94 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_manual_pong() -> Result<()> {
+        let url = crate::test::engine_io_server()?;
+        let socket = builder(url).auto_pong(false).build()?;
+
+        socket.connect()?;
+
+        let mut iter = socket.iter();
+        // hello client
+        iter.next();
+        // Ping
+        assert_eq!(iter.next().unwrap()?.packet_id, PacketId::Ping);
+        socket.pong()?;
+        // the server only pings again if it got the pong
+        assert_eq!(iter.next().unwrap()?.packet_id, PacketId::Ping);
+
+        assert!(socket.is_connected()?);
+        socket.disconnect()?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "raw")]
+    #[test]
+    fn test_emit_raw() -> Result<()> {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let server = std::thread::spawn(move || {
+            crate::test::serve_http(
+                &listener,
+                &[
+                    r#"0{"sid":"raw","upgrades":[],"pingInterval":25000,"pingTimeout":20000}"#,
+                    "ok",
+                ],
+            )
+        });
+
+        let url = Url::parse(&format!("http://127.0.0.1:{}/", port))?;
+        let client = ClientBuilder::new(url).build_polling()?;
+        client.emit_raw(Bytes::from_static(b"not a packet"))?;
+
+        assert_eq!(server.join().unwrap()?[1], b"not a packet");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_handshake() -> Result<()> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let url = crate::test::engine_io_server()?;
+        let socket = builder(url)
+            .on_open(move |handshake| tx.send(handshake).unwrap())
+            .build()?;
+
+        let handshake = socket.handshake();
+        // the defaults of the engine.io test server
+        assert_eq!(handshake.ping_interval, 25000);
+        assert_eq!(handshake.ping_timeout, 20000);
+        assert!(!handshake.sid.is_empty());
+
+        // the same handshake is handed to `on_open` once connected
+        socket.connect()?;
+        assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), handshake);
+
+        socket.close()
+    }
+
+    #[test]
+    fn test_path() -> Result<()> {
+        let url = Url::parse("http://localhost:4201/")?;
+        let builder = ClientBuilder::new(url).path("/custom/");
+
+        assert_eq!(builder.url.path(), "/custom/");
+        assert_eq!(builder.url.query(), Some("EIO=4"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_connection_custom_path() -> Result<()> {
+        // the test server is mounted at `/custom/` as well
+        let url = crate::test::engine_io_server()?;
+        let socket = builder(url).path(String::from("/custom/")).build()?;
+        test_connection(socket)
+    }
+
     #[test]
     fn test_connection_dynamic() -> Result<()> {
         let url = crate::test::engine_io_server()?;
@@ -500,6 +703,102 @@ mod test {
         test_connection(socket)
     }
 
+    #[test]
+    fn test_handshake_headers() -> Result<()> {
+        let url = crate::test::engine_io_polling_server()?;
+        let socket = builder(url).build_polling()?;
+
+        let headers = socket.handshake_headers();
+        assert_eq!(
+            headers.get("x-handshake-test"),
+            Some(&HeaderValue::from("engine.io"))
+        );
+
+        let url = crate::test::engine_io_server()?;
+        let socket = builder(url).build_websocket()?;
+        assert!(socket.handshake_headers().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_connect_retries() -> Result<()> {
+        use std::net::TcpListener;
+
+        // reserve a free port, the server only starts listening on it after a delay
+        let port = crate::test::free_port()?;
+        let server = std::thread::spawn(move || -> Result<()> {
+            std::thread::sleep(Duration::from_millis(100));
+            let listener = TcpListener::bind(("127.0.0.1", port))?;
+            crate::test::serve_http(
+                &listener,
+                &[r#"0{"sid":"retried","upgrades":[],"pingInterval":25000,"pingTimeout":20000}"#],
+            )?;
+            Ok(())
+        });
+
+        let url = Url::parse(&format!("http://127.0.0.1:{}/", port))?;
+        assert!(ClientBuilder::new(url.clone()).build_polling().is_err());
+
+        // the handshake only succeeds if the client keeps retrying until the server is up
+        ClientBuilder::new(url)
+            .connect_retries(50, Duration::from_millis(20))
+            .build_polling()?;
+
+        server.join().unwrap()
+    }
+
+    #[test]
+    fn test_connect_retries_websocket() -> Result<()> {
+        use std::net::TcpListener;
+
+        // reserve a free port, the server only starts listening on it after a delay
+        let port = crate::test::free_port()?;
+        let server = std::thread::spawn(move || -> Result<()> {
+            std::thread::sleep(Duration::from_millis(100));
+            let listener = TcpListener::bind(("127.0.0.1", port))?;
+            let (stream, _) = listener.accept()?;
+            let mut websocket = tungstenite::accept(stream).unwrap();
+            websocket.write_message(tungstenite::Message::Text(
+                r#"0{"sid":"retried","upgrades":[],"pingInterval":25000,"pingTimeout":20000}"#
+                    .to_owned(),
+            ))?;
+            Ok(())
+        });
+
+        let url = Url::parse(&format!("http://127.0.0.1:{}/", port))?;
+        assert!(ClientBuilder::new(url.clone()).build_websocket().is_err());
+
+        let client = ClientBuilder::new(url)
+            .connect_retries(50, Duration::from_millis(20))
+            .build_websocket()?;
+        assert_eq!(client.handshake().sid, "retried");
+
+        server.join().unwrap()
+    }
+
+    #[test]
+    fn test_connect_retries_fail_fast() -> Result<()> {
+        use std::net::TcpListener;
+
+        // a server that answers with garbage must not be asked again
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = Url::parse(&format!("http://{}/", listener.local_addr()?))?;
+        let server = std::thread::spawn(move || -> Result<()> {
+            crate::test::serve_http(&listener, &["invalid"])?;
+            Ok(())
+        });
+
+        let start = std::time::Instant::now();
+        assert!(ClientBuilder::new(url)
+            .connect_retries(50, Duration::from_secs(1))
+            .build_polling()
+            .is_err());
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        server.join().unwrap()
+    }
+
     #[test]
     fn test_connection_wss() -> Result<()> {
         let url = crate::test::engine_io_polling_server()?;
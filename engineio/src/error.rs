@@ -56,6 +56,18 @@ pub enum Error {
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Whether the server couldn't be reached, so trying again later might succeed,
+    /// e.g. while the server is still starting.
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            Error::IncompleteResponseFromReqwest(err) => err.is_connect() || err.is_timeout(),
+            Error::IncompleteIo(_) | Error::WebsocketError(TungsteniteError::Io(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 impl<T> From<std::sync::PoisonError<T>> for Error {
     fn from(_: std::sync::PoisonError<T>) -> Self {
         Self::InvalidPoisonedLock()
@@ -39,6 +39,12 @@ impl From<String> for HeaderName {
     }
 }
 
+impl From<&str> for HeaderName {
+    fn from(string: &str) -> Self {
+        Self::from(string.to_owned())
+    }
+}
+
 impl TryFrom<HeaderName> for HttpHeaderName {
     type Error = Error;
     fn try_from(
@@ -102,6 +108,18 @@ impl TryFrom<HeaderMap> for HttpHeaderMap {
     }
 }
 
+impl From<HttpHeaderMap> for HeaderMap {
+    fn from(headers: HttpHeaderMap) -> Self {
+        let mut result = HeaderMap::new();
+        // names with several values keep the last one
+        for (key, value) in &headers {
+            result.insert(key.clone(), value.clone());
+        }
+
+        result
+    }
+}
+
 impl IntoIterator for HeaderMap {
     type Item = (HeaderName, HeaderValue);
     type IntoIter = IntoIter;
@@ -126,6 +144,14 @@ impl HeaderMap {
     ) -> Option<HeaderValue> {
         self.map.insert(key.into(), value.into())
     }
+
+    pub fn get<T: Into<HeaderName>>(&self, key: T) -> Option<&HeaderValue> {
+        self.map.get(&key.into())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
 }
 
 impl Iterator for IntoIter {
@@ -107,7 +107,8 @@ pub(crate) mod test {
     const CERT_PATH: &str = "../ci/cert/ca.crt";
     use native_tls::Certificate;
     use std::fs::File;
-    use std::io::Read;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
 
     pub(crate) fn tls_connector() -> error::Result<TlsConnector> {
         let cert_path = std::env::var("CA_CERT_PATH").unwrap_or_else(|_| CERT_PATH.to_owned());
@@ -145,4 +146,61 @@ pub(crate) mod test {
             .unwrap_or_else(|_| SERVER_URL_SECURE.to_owned());
         Ok(Url::parse(&url)?)
     }
+
+    /// Reserves a free local port, nothing listens on it once this returns.
+    pub(crate) fn free_port() -> crate::error::Result<u16> {
+        Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+    }
+
+    /// Answers one http request per body on `listener` and returns the bodies
+    /// of the requests it received.
+    pub(crate) fn serve_http(
+        listener: &TcpListener,
+        bodies: &[&str],
+    ) -> crate::error::Result<Vec<Vec<u8>>> {
+        let mut requests = Vec::new();
+        for body in bodies {
+            let (mut stream, _) = listener.accept()?;
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            // read until the headers and the announced content are complete
+            let body_start = loop {
+                let n = stream.read(&mut buf)?;
+                if n == 0 {
+                    break request.len();
+                }
+                request.extend_from_slice(&buf[..n]);
+                if let Some(pos) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+            };
+            let content_length = String::from_utf8_lossy(&request[..body_start])
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    if name.eq_ignore_ascii_case("content-length") {
+                        value.trim().parse::<usize>().ok()
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(0);
+            while request.len() < body_start + content_length {
+                let n = stream.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )?;
+            requests.push(request.split_off(body_start));
+        }
+        Ok(requests)
+    }
 }
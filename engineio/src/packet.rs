@@ -22,12 +22,25 @@ pub enum PacketId {
     Noop,
 }
 
+impl PacketId {
+    /// Returns the character that identifies this `PacketId` on the wire.
+    pub fn as_char(self) -> char {
+        match self {
+            PacketId::Open => '0',
+            PacketId::Close => '1',
+            PacketId::Ping => '2',
+            PacketId::Pong => '3',
+            PacketId::Message => '4',
+            PacketId::MessageBinary => 'b',
+            PacketId::Upgrade => '5',
+            PacketId::Noop => '6',
+        }
+    }
+}
+
 impl From<PacketId> for String {
     fn from(packet: PacketId) -> Self {
-        match packet {
-            PacketId::MessageBinary => "b".to_owned(),
-            _ => (u8::from(packet)).to_string(),
-        }
+        packet.as_char().to_string()
     }
 }
 
@@ -58,11 +71,23 @@ impl TryFrom<u8> for PacketId {
             4 | b'4' => Ok(PacketId::Message),
             5 | b'5' => Ok(PacketId::Upgrade),
             6 | b'6' => Ok(PacketId::Noop),
+            b'b' => Ok(PacketId::MessageBinary),
             _ => Err(Error::InvalidPacketId(b)),
         }
     }
 }
 
+impl TryFrom<char> for PacketId {
+    type Error = Error;
+    /// Converts a wire character into the corresponding `PacketId`.
+    fn try_from(c: char) -> Result<PacketId> {
+        match c {
+            '0'..='6' | 'b' => PacketId::try_from(c as u8),
+            _ => Err(Error::InvalidPacketId(c as u8)),
+        }
+    }
+}
+
 /// A `Packet` sent via the `engine.io` protocol.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Packet {
@@ -79,6 +104,10 @@ pub struct HandshakePacket {
     pub ping_interval: u64,
     #[serde(rename = "pingTimeout")]
     pub ping_timeout: u64,
+    /// Any further fields the server sent along, e.g. `maxPayload` or custom
+    /// fields of socket.io compatible servers.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl TryFrom<Packet> for HandshakePacket {
@@ -166,17 +195,12 @@ impl TryFrom<Bytes> for Payload {
     /// Decodes a `payload` which in the `engine.io` context means a chain of normal
     /// packets separated by a certain SEPARATOR, in this case the delimiter `\x30`.
     fn try_from(payload: Bytes) -> Result<Self> {
-        let mut vec = Vec::new();
-        let mut last_index = 0;
+        let vec = decode_payload(payload)?;
 
-        for i in 0..payload.len() {
-            if *payload.get(i).unwrap() as char == Self::SEPARATOR {
-                vec.push(Packet::try_from(payload.slice(last_index..i))?);
-                last_index = i + 1;
-            }
+        // a response to a poll always carries at least one packet
+        if vec.is_empty() {
+            return Err(Error::IncompletePacket());
         }
-        // push the last packet as well
-        vec.push(Packet::try_from(payload.slice(last_index..payload.len()))?);
 
         Ok(Payload(vec))
     }
@@ -188,17 +212,49 @@ impl TryFrom<Payload> for Bytes {
     /// normal `packets` separated by a SEPARATOR, in this case the delimiter
     /// `\x30`.
     fn try_from(packets: Payload) -> Result<Self> {
-        let mut buf = BytesMut::new();
-        for packet in packets {
-            // at the moment no base64 encoding is used
-            buf.extend(Bytes::from(packet.clone()));
-            buf.put_u8(Payload::SEPARATOR as u8);
+        Ok(encode_payload(&packets.0))
+    }
+}
+
+/// Decodes an `engine.io` payload, the batch of packets sent in the body of a polling
+/// request, into its packets. An empty payload holds no packets.
+///
+/// Binary packets are always expected to be base64 encoded and prefixed with `b`.
+/// Unlike version 3, version 4 of the protocol has no binary payload encoding, so
+/// there's no flag to choose one.
+pub fn decode_payload(payload: Bytes) -> Result<Vec<Packet>> {
+    let mut vec = Vec::new();
+    let mut last_index = 0;
+
+    for i in 0..payload.len() {
+        if *payload.get(i).unwrap() as char == Payload::SEPARATOR {
+            // empty segments, e.g. from a trailing separator, carry no packet
+            if i > last_index {
+                vec.push(Packet::try_from(payload.slice(last_index..i))?);
+            }
+            last_index = i + 1;
         }
+    }
+    // push the last packet as well
+    if payload.len() > last_index {
+        vec.push(Packet::try_from(payload.slice(last_index..payload.len()))?);
+    }
 
-        // remove the last separator
-        let _ = buf.split_off(buf.len() - 1);
-        Ok(buf.freeze())
+    Ok(vec)
+}
+
+/// Encodes packets into an `engine.io` payload that can be sent as the body of a
+/// polling request. Binary packets are base64 encoded.
+pub fn encode_payload(packets: &[Packet]) -> Bytes {
+    let mut buf = BytesMut::new();
+    for (i, packet) in packets.iter().enumerate() {
+        if i > 0 {
+            buf.put_u8(Payload::SEPARATOR as u8);
+        }
+        buf.extend(Bytes::from(packet.clone()));
     }
+
+    buf.freeze()
 }
 
 #[derive(Clone, Debug)]
@@ -280,6 +336,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_decode_payload_empty_segments() -> Result<()> {
+        let packets = Payload::try_from(Bytes::from_static(b"4msg1\x1e4msg2\x1e"))?;
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0], Packet::new(PacketId::Message, "msg1"));
+        assert_eq!(packets[1], Packet::new(PacketId::Message, "msg2"));
+
+        let packets = Payload::try_from(Bytes::from_static(b"\x1e4msg1\x1e\x1e4msg2"))?;
+        assert_eq!(packets.len(), 2);
+
+        assert!(Payload::try_from(Bytes::from_static(b"\x1e\x1e")).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_binary_payload() {
         let data = Bytes::from_static(b"bSGVsbG8=\x1ebSGVsbG9Xb3JsZA==\x1ebSGVsbG8=");
@@ -336,6 +407,33 @@ mod tests {
         assert!(matches!(sut.unwrap_err(), Error::InvalidPacketId(42)));
     }
 
+    #[test]
+    fn test_packet_id_char_conversion() {
+        assert_eq!(PacketId::try_from('0').unwrap(), PacketId::Open);
+        assert_eq!(PacketId::try_from('2').unwrap(), PacketId::Ping);
+        assert_eq!(PacketId::try_from('b').unwrap(), PacketId::MessageBinary);
+        assert_eq!(PacketId::try_from(b'b').unwrap(), PacketId::MessageBinary);
+        assert!(matches!(
+            PacketId::try_from('x').unwrap_err(),
+            Error::InvalidPacketId(b'x')
+        ));
+        assert!(PacketId::try_from('\u{2}').is_err());
+
+        for packet_id in [
+            PacketId::Open,
+            PacketId::Close,
+            PacketId::Ping,
+            PacketId::Pong,
+            PacketId::Message,
+            PacketId::MessageBinary,
+            PacketId::Upgrade,
+            PacketId::Noop,
+        ] {
+            assert_eq!(PacketId::try_from(packet_id.as_char()).unwrap(), packet_id);
+            assert_eq!(String::from(packet_id), packet_id.as_char().to_string());
+        }
+    }
+
     #[test]
     fn test_handshake_packet() {
         assert!(
@@ -346,6 +444,7 @@ mod tests {
             ping_timeout: 1000,
             sid: "Test".to_owned(),
             upgrades: vec!["websocket".to_owned(), "test".to_owned()],
+            extra: serde_json::Map::new(),
         };
         let encoded: String = serde_json::to_string(&packet).unwrap();
 
@@ -355,4 +454,46 @@ mod tests {
                 .unwrap()
         );
     }
+    #[test]
+    fn test_handshake_packet_extra_fields() {
+        let data = r#"{"sid":"Test","upgrades":[],"pingInterval":10000,"pingTimeout":1000,"maxPayload":1000000,"region":"eu"}"#;
+        let packet =
+            HandshakePacket::try_from(Packet::new(PacketId::Open, Bytes::from(data))).unwrap();
+
+        assert_eq!(packet.sid, "Test");
+        assert_eq!(
+            packet.extra.get("maxPayload"),
+            Some(&serde_json::json!(1000000))
+        );
+        assert_eq!(packet.extra.get("region"), Some(&serde_json::json!("eu")));
+
+        // the extra fields are serialized alongside the standard ones
+        let encoded: serde_json::Value = serde_json::to_value(&packet).unwrap();
+        assert_eq!(
+            encoded,
+            serde_json::from_str::<serde_json::Value>(data).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_payload() -> Result<()> {
+        let packets = vec![
+            Packet::new(PacketId::Message, Bytes::from_static(b"Hello")),
+            Packet::new(
+                PacketId::MessageBinary,
+                Bytes::from_static(&[0, 1, 0x1e, 255]),
+            ),
+            Packet::new(PacketId::Ping, Bytes::new()),
+        ];
+
+        let payload = encode_payload(&packets);
+        assert_eq!(payload, Bytes::from_static(b"4Hello\x1ebAAEe/w==\x1e2"));
+        assert_eq!(decode_payload(payload)?, packets);
+
+        assert!(encode_payload(&[]).is_empty());
+        assert!(decode_payload(Bytes::new())?.is_empty());
+        assert!(Payload::try_from(Bytes::new()).is_err());
+
+        Ok(())
+    }
 }
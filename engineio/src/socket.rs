@@ -20,7 +20,7 @@ pub struct Socket {
     on_close: OptionalCallback<()>,
     on_data: OptionalCallback<Bytes>,
     on_error: OptionalCallback<String>,
-    on_open: OptionalCallback<()>,
+    on_open: OptionalCallback<HandshakePacket>,
     on_packet: OptionalCallback<Packet>,
     connected: Arc<AtomicBool>,
     last_ping: Arc<Mutex<Instant>>,
@@ -37,7 +37,7 @@ impl Socket {
         on_close: OptionalCallback<()>,
         on_data: OptionalCallback<Bytes>,
         on_error: OptionalCallback<String>,
-        on_open: OptionalCallback<()>,
+        on_open: OptionalCallback<HandshakePacket>,
         on_packet: OptionalCallback<Packet>,
     ) -> Self {
         Socket {
@@ -62,7 +62,7 @@ impl Socket {
         self.connected.store(true, Ordering::Release);
 
         if let Some(on_open) = self.on_open.as_ref() {
-            spawn_scoped!(on_open(()));
+            spawn_scoped!(on_open(self.connection_data.as_ref().clone()));
         }
 
         // set the last ping to now and set the connected state
@@ -113,6 +113,12 @@ impl Socket {
         Ok(())
     }
 
+    /// Writes `data` on the transport as is, without any engine.io framing.
+    #[cfg(feature = "raw")]
+    pub(crate) fn emit_raw(&self, data: Bytes) -> Result<()> {
+        self.transport.as_transport().emit(data, false)
+    }
+
     /// Polls for next payload
     pub(crate) fn poll(&self) -> Result<Option<Packet>> {
         loop {
@@ -160,6 +166,11 @@ impl Socket {
         Ok(self.connected.load(Ordering::Acquire))
     }
 
+    /// Returns the handshake the server answered the connection with.
+    pub(crate) fn handshake(&self) -> &HandshakePacket {
+        &self.connection_data
+    }
+
     pub(crate) fn pinged(&self) -> Result<()> {
         *self.last_ping.lock()? = Instant::now();
         Ok(())
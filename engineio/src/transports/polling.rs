@@ -44,6 +44,14 @@ impl PollingTransport {
             base_url: Arc::new(RwLock::new(url)),
         }
     }
+
+    /// Performs a single poll request and returns the body along with the
+    /// headers of the response.
+    pub(crate) fn poll_with_headers(&self) -> Result<(Bytes, HeaderMap)> {
+        let response = self.client.lock()?.get(self.address()?).send()?;
+        let headers = response.headers().clone();
+        Ok((response.bytes()?, headers))
+    }
 }
 
 impl Transport for PollingTransport {
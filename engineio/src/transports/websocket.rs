@@ -21,11 +21,23 @@ pub struct WebsocketTransport {
 impl WebsocketTransport {
     /// Creates an instance of `WebsocketTransport`.
     pub fn new(base_url: Url, headers: Option<HeaderMap>) -> Result<Self> {
+        Self::with_nodelay(base_url, headers, true)
+    }
+
+    /// Creates an instance like [`WebsocketTransport::new`], `nodelay` sets whether
+    /// Nagle's algorithm is disabled for the connection.
+    pub(crate) fn with_nodelay(
+        base_url: Url,
+        headers: Option<HeaderMap>,
+        nodelay: bool,
+    ) -> Result<Self> {
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()?;
 
-        let inner = runtime.block_on(AsyncWebsocketTransport::new(base_url, headers))?;
+        let inner = runtime.block_on(AsyncWebsocketTransport::with_nodelay(
+            base_url, headers, nodelay,
+        ))?;
 
         Ok(WebsocketTransport {
             runtime: Arc::new(runtime),
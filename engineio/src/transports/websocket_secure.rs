@@ -26,13 +26,24 @@ impl WebsocketSecureTransport {
         base_url: Url,
         tls_config: Option<TlsConnector>,
         headers: Option<HeaderMap>,
+    ) -> Result<Self> {
+        Self::with_nodelay(base_url, tls_config, headers, true)
+    }
+
+    /// Creates an instance like [`WebsocketSecureTransport::new`], `nodelay` sets
+    /// whether Nagle's algorithm is disabled for the connection.
+    pub(crate) fn with_nodelay(
+        base_url: Url,
+        tls_config: Option<TlsConnector>,
+        headers: Option<HeaderMap>,
+        nodelay: bool,
     ) -> Result<Self> {
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()?;
 
         let inner = runtime.block_on(AsyncWebsocketSecureTransport::new(
-            base_url, tls_config, headers,
+            base_url, tls_config, headers, nodelay,
         ))?;
 
         Ok(WebsocketSecureTransport {
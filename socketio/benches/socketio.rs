@@ -0,0 +1,23 @@
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rust_socketio::Packet;
+
+/// An event whose arguments are about 1MB of JSON.
+pub fn socket_io_large_event() -> Bytes {
+    let items = vec!["\"abcdefghij\""; 80_000].join(",");
+    Bytes::from(format!("2[\"big\",[{}]]", items))
+}
+
+pub fn criterion_socket_io_decode_large_event(c: &mut Criterion) {
+    let payload = socket_io_large_event();
+
+    let mut group = c.benchmark_group("socket io");
+    group.throughput(Throughput::Bytes(payload.len() as u64));
+    group.bench_function("decode 1MB event", |b| {
+        b.iter(|| Packet::try_from(black_box(&payload)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_socket_io_decode_large_event);
+criterion_main!(benches);
@@ -13,6 +13,7 @@ fn main() {
         match payload {
             Payload::String(str) => println!("Received string: {}", str),
             Payload::Binary(bin_data) => println!("Received bytes: {:#?}", bin_data),
+            _ => {}
         }
         socket
             .emit("test", json!({"got ack": true}))
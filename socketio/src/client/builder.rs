@@ -1,15 +1,16 @@
 use super::super::{event::Event, payload::Payload};
 use super::callback::Callback;
-use crate::Client;
+use crate::{Client, Packet};
 use native_tls::TlsConnector;
 use rust_engineio::client::ClientBuilder as EngineIoClientBuilder;
 use rust_engineio::header::{HeaderMap, HeaderValue};
 use url::Url;
 
-use crate::client::callback::{SocketAnyCallback, SocketCallback};
+use crate::client::callback::{SocketAnyCallback, SocketCallback, SocketPacketCallback};
 use crate::error::Result;
 use crate::socket::Socket as InnerSocket;
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Flavor of Engine.IO transport.
 #[derive(Clone, Eq, PartialEq)]
@@ -32,11 +33,16 @@ pub struct ClientBuilder {
     address: String,
     on: HashMap<Event, Callback<SocketCallback>>,
     on_any: Option<Callback<SocketAnyCallback>>,
+    on_packet: Option<Callback<SocketPacketCallback>>,
     namespace: String,
     tls_config: Option<TlsConnector>,
     opening_headers: Option<HeaderMap>,
     transport_type: TransportType,
     auth: Option<serde_json::Value>,
+    connect_retries: Option<(usize, Duration)>,
+    path: Option<String>,
+    max_pending_acks: Option<usize>,
+    tcp_nodelay: bool,
 }
 
 impl ClientBuilder {
@@ -54,6 +60,7 @@ impl ClientBuilder {
     ///            match payload {
     ///                Payload::String(str) => println!("Received: {}", str),
     ///                Payload::Binary(bin_data) => println!("Received bytes: {:#?}", bin_data),
+    ///                _ => {}
     ///            }
     /// };
     ///
@@ -75,11 +82,16 @@ impl ClientBuilder {
             address: address.into(),
             on: HashMap::new(),
             on_any: None,
+            on_packet: None,
             namespace: "/".to_owned(),
             tls_config: None,
             opening_headers: None,
             transport_type: TransportType::Any,
             auth: None,
+            connect_retries: None,
+            path: None,
+            max_pending_acks: None,
+            tcp_nodelay: true,
         }
     }
 
@@ -94,6 +106,22 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the path the socket.io server is mounted at, e.g. `/custom/`. This
+    /// takes precedence over the path of the address, which otherwise defaults
+    /// to `/socket.io/`.
+    /// # Example
+    /// ```rust
+    /// use rust_socketio::ClientBuilder;
+    ///
+    /// let socket = ClientBuilder::new("http://localhost:4200")
+    ///     .path("/custom/")
+    ///     .connect();
+    /// ```
+    pub fn path<T: Into<String>>(mut self, path: T) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
     /// Registers a new callback for a certain [`crate::event::Event`]. The event could either be
     /// one of the common events like `message`, `error`, `connect`, `close` or a custom
     /// event defined by a string, e.g. `onPayment` or `foo`.
@@ -108,6 +136,7 @@ impl ClientBuilder {
     ///            match payload {
     ///                Payload::String(str) => println!("Received: {}", str),
     ///                Payload::Binary(bin_data) => println!("Received bytes: {:#?}", bin_data),
+    ///                _ => {}
     ///            }
     ///     })
     ///     .on("error", |err, _| eprintln!("Error: {:#?}", err))
@@ -147,6 +176,31 @@ impl ClientBuilder {
         self
     }
 
+    /// Registers a Callback for every raw [`crate::Packet`] received in the namespace,
+    /// before it is dispatched to the other callbacks. The packet's `data` holds the
+    /// JSON exactly as it was sent, including the placeholders of binary attachments,
+    /// and its attachments are ordered by their `num`, so it can be relayed as is with
+    /// [`Client::send_packet`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use rust_socketio::{ClientBuilder, Packet};
+    ///
+    /// let client = ClientBuilder::new("http://localhost:4200/")
+    ///     .on_packet(|packet: Packet, _client| {
+    ///         println!("{:?} {:?}", packet.packet_type, packet.data);
+    ///     })
+    ///     .connect();
+    ///
+    /// ```
+    pub fn on_packet<F>(mut self, callback: F) -> Self
+    where
+        F: for<'a> FnMut(Packet, Client) + 'static + Sync + Send,
+    {
+        self.on_packet = Some(Callback::<SocketPacketCallback>::new(callback));
+        self
+    }
+
     /// Uses a preconfigured TLS connector for secure communication. This configures
     /// both the `polling` as well as the `websocket` transport type.
     /// # Example
@@ -238,6 +292,61 @@ impl ClientBuilder {
         self
     }
 
+    /// Retries the initial engine.io handshake up to `retries` times, waiting `delay`
+    /// between the attempts, as long as the server can't be reached, e.g. while it's
+    /// still starting. This applies to every [`TransportType`] and is independent of
+    /// the reconnection after an established connection dropped.
+    /// # Example
+    /// ```rust
+    /// use rust_socketio::ClientBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let socket = ClientBuilder::new("http://localhost:4200/")
+    ///     .connect_retries(5, Duration::from_millis(200))
+    ///     .connect()
+    ///     .expect("connection failed");
+    /// ```
+    pub fn connect_retries(mut self, retries: usize, delay: Duration) -> Self {
+        self.connect_retries = Some((retries, delay));
+
+        self
+    }
+
+    /// Limits the number of acks that wait for the server at the same time, acks that
+    /// timed out don't count. Once the limit is reached, `emit_with_ack` rejects new
+    /// messages with [`crate::Error::IllegalPendingAcks`] instead of growing the
+    /// outstanding acks without bounds. There is no limit by default.
+    /// # Example
+    /// ```rust
+    /// use rust_socketio::ClientBuilder;
+    ///
+    /// let socket = ClientBuilder::new("http://localhost:4200/")
+    ///     .max_pending_acks(1024)
+    ///     .connect()
+    ///     .expect("connection failed");
+    /// ```
+    pub fn max_pending_acks(mut self, max_pending_acks: usize) -> Self {
+        self.max_pending_acks = Some(max_pending_acks);
+        self
+    }
+
+    /// Whether Nagle's algorithm is disabled for websocket connections, which is the
+    /// default. Enabling it batches small packets at the cost of latency.
+    /// # Example
+    /// ```rust
+    /// use rust_socketio::{ClientBuilder, TransportType};
+    ///
+    /// let socket = ClientBuilder::new("http://localhost:4200/")
+    ///     .transport_type(TransportType::Websocket)
+    ///     .tcp_nodelay(false)
+    ///     .connect()
+    ///     .expect("connection failed");
+    /// ```
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
     /// Connects the socket to a certain endpoint. This returns a connected
     /// [`Client`] instance. This method returns an [`std::result::Result::Err`]
     /// value if something goes wrong during connection. Also starts a separate
@@ -266,12 +375,46 @@ impl ClientBuilder {
         Ok(socket)
     }
 
+    /// Like [`ClientBuilder::connect`], but only returns once the server accepted
+    /// the connection to the namespace. Fails if the server rejected it or didn't
+    /// answer within `timeout`, in which case the client is disconnected again.
+    /// # Example
+    /// ```rust
+    /// use rust_socketio::ClientBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let socket = ClientBuilder::new("http://localhost:4200/")
+    ///     .namespace("/admin")
+    ///     .connect_with_timeout(Duration::from_secs(5))
+    ///     .expect("namespace not joined");
+    /// ```
+    pub fn connect_with_timeout(self, timeout: Duration) -> Result<Client> {
+        let socket = self.connect_manual()?;
+
+        if let Err(err) = socket.wait_for_connect(timeout) {
+            let _ = socket.disconnect();
+            return Err(err);
+        }
+
+        Ok(socket)
+    }
+
     //TODO: 0.3.X stabilize
     pub(crate) fn connect_manual(self) -> Result<Client> {
+        let socket = self.build()?;
+        socket.connect()?;
+
+        Ok(socket)
+    }
+
+    /// Creates the [`Client`] without connecting it yet.
+    pub(crate) fn build(self) -> Result<Client> {
         // Parse url here rather than in new to keep new returning Self.
         let mut url = Url::parse(&self.address)?;
 
-        if url.path() == "/" {
+        if let Some(path) = &self.path {
+            url.set_path(path);
+        } else if url.path() == "/" {
             url.set_path("/socket.io/");
         }
 
@@ -283,25 +426,29 @@ impl ClientBuilder {
         if let Some(headers) = self.opening_headers {
             builder = builder.headers(headers);
         }
+        if let Some((retries, delay)) = self.connect_retries {
+            builder = builder.connect_retries(retries, delay);
+        }
+        builder = builder.tcp_nodelay(self.tcp_nodelay);
 
         let transport_type = self.transport_type.clone();
-        let socket = Client::new(
-            Box::new(move || build_socket(transport_type.clone(), builder.clone())),
+        let namespace = self.namespace.clone();
+        Client::new(
+            Box::new(move || build_socket(transport_type.clone(), builder.clone(), &namespace)),
             &self.namespace,
             self.on,
             self.on_any,
+            self.on_packet,
             self.auth,
-        )?;
-
-        socket.connect()?;
-
-        Ok(socket)
+            self.max_pending_acks,
+        )
     }
 }
 
 fn build_socket(
     transport_type: TransportType,
     builder: EngineIoClientBuilder,
+    namespace: &str,
 ) -> Result<InnerSocket> {
     let engine_client = match transport_type {
         TransportType::Any => builder.build_with_fallback()?,
@@ -310,5 +457,5 @@ fn build_socket(
         TransportType::WebsocketUpgrade => builder.build_websocket_with_upgrade()?,
     };
 
-    InnerSocket::new(engine_client)
+    InnerSocket::new(engine_client, namespace)
 }
@@ -4,11 +4,13 @@ use std::{
 };
 
 use super::Client;
-use crate::{Event, Payload};
+use crate::{Event, Packet, Payload};
 
 pub(crate) type SocketCallback = Box<dyn for<'a> FnMut(Payload, Client) + 'static + Sync + Send>;
 pub(crate) type SocketAnyCallback =
     Box<dyn for<'a> FnMut(Event, Payload, Client) + 'static + Sync + Send>;
+pub(crate) type SocketPacketCallback =
+    Box<dyn for<'a> FnMut(Packet, Client) + 'static + Sync + Send>;
 
 pub(crate) struct Callback<T> {
     inner: T,
@@ -79,3 +81,36 @@ impl Callback<SocketAnyCallback> {
         }
     }
 }
+
+// SocketPacketCallback implementations
+
+impl Debug for Callback<SocketPacketCallback> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Callback")
+    }
+}
+
+impl Deref for Callback<SocketPacketCallback> {
+    type Target = dyn for<'a> FnMut(Packet, Client) + 'static + Sync + Send;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref()
+    }
+}
+
+impl DerefMut for Callback<SocketPacketCallback> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner.as_mut()
+    }
+}
+
+impl Callback<SocketPacketCallback> {
+    pub(crate) fn new<T>(callback: T) -> Self
+    where
+        T: for<'a> FnMut(Packet, Client) + 'static + Sync + Send,
+    {
+        Callback {
+            inner: Box::new(callback),
+        }
+    }
+}
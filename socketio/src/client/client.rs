@@ -2,22 +2,30 @@ pub use super::super::{event::Event, payload::Payload};
 use super::callback::Callback;
 use crate::packet::{Packet, PacketId};
 use crate::Error;
-use rand::{thread_rng, Rng};
 
-use crate::client::callback::{SocketAnyCallback, SocketCallback};
+use crate::client::callback::{SocketAnyCallback, SocketCallback, SocketPacketCallback};
 use crate::error::Result;
 use backoff::backoff::Backoff;
 use backoff::ExponentialBackoff;
 use std::collections::HashMap;
 use std::ops::DerefMut;
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::time::Duration;
 use std::time::Instant;
 
 use crate::socket::Socket as InnerSocket;
+use serde::de::DeserializeOwned;
 
 type BuildSocketFn = dyn Fn() -> Result<InnerSocket> + Send + Sync;
 
+/// The server's answer to the latest namespace connect request, `None` until it
+/// arrived, the error message of a `ConnectError` if it was rejected.
+type ConnectAck = (Mutex<Option<std::result::Result<(), String>>>, Condvar);
+
+/// A callback that is locked on its own, so it can be dispatched without holding
+/// the collection it's registered in.
+type SharedCallback<T> = Arc<Mutex<Callback<T>>>;
+
 /// Represents an `Ack` as given back to the caller. Holds the internal `id` as
 /// well as the current ack'ed state. Holds data which will be accessible as
 /// soon as the ack'ed state is set to true. An `Ack` that didn't get ack'ed
@@ -30,6 +38,35 @@ pub struct Ack {
     callback: Callback<SocketCallback>,
 }
 
+/// Hands out the ids of acks, counting up from `0` and wrapping around after
+/// `ceiling`. Ids of acks that are still outstanding are skipped.
+#[derive(Debug)]
+struct AckIdAllocator {
+    next: i32,
+    ceiling: i32,
+}
+
+impl AckIdAllocator {
+    fn new(ceiling: i32) -> Self {
+        AckIdAllocator { next: 0, ceiling }
+    }
+
+    /// Returns the next id that isn't used by any of the `outstanding` acks.
+    fn allocate(&mut self, outstanding: &[Ack]) -> Result<i32> {
+        if outstanding.len() > self.ceiling as usize {
+            return Err(Error::IllegalAckIdAllocation());
+        }
+
+        loop {
+            let id = self.next;
+            self.next = if id >= self.ceiling { 0 } else { id + 1 };
+            if !outstanding.iter().any(|ack| ack.id == id) {
+                return Ok(id);
+            }
+        }
+    }
+}
+
 /// A socket which handles communication with the server. It's initialized with
 /// a specific address as well as an optional namespace to connect to. If `None`
 /// is given the server will connect to the default namespace `"/"`.
@@ -43,9 +80,14 @@ struct Inner {
     /// The inner socket client to delegate the methods to.
     socket: Option<Arc<RwLock<InnerSocket>>>,
     socket_fn: Arc<Mutex<Box<BuildSocketFn>>>,
-    on: Arc<RwLock<HashMap<Event, Callback<SocketCallback>>>>,
-    on_any: Arc<RwLock<Option<Callback<SocketAnyCallback>>>>,
+    on: Arc<RwLock<HashMap<Event, SharedCallback<SocketCallback>>>>,
+    on_any: Arc<RwLock<Option<SharedCallback<SocketAnyCallback>>>>,
+    on_packet: Arc<RwLock<Option<SharedCallback<SocketPacketCallback>>>>,
     outstanding_acks: Arc<RwLock<Vec<Ack>>>,
+    ack_ids: Arc<Mutex<AckIdAllocator>>,
+    // upper bound of acks waiting for the server at the same time
+    max_pending_acks: Option<usize>,
+    connect_ack: Arc<ConnectAck>,
     // namespace, for multiplexing messages
     nsp: String,
     // Data sent in opening header
@@ -63,16 +105,30 @@ impl Client {
         namespace: T,
         on: HashMap<Event, Callback<SocketCallback>>,
         on_any: Option<Callback<SocketAnyCallback>>,
+        on_packet: Option<Callback<SocketPacketCallback>>,
         auth: Option<serde_json::Value>,
+        max_pending_acks: Option<usize>,
     ) -> Result<Self> {
         Ok(Client {
             inner: Arc::new(RwLock::new(Inner {
                 socket: None,
                 socket_fn: Arc::new(Mutex::new(socket_fn)),
                 nsp: namespace.into(),
-                on: Arc::new(RwLock::new(on)),
-                on_any: Arc::new(RwLock::new(on_any)),
+                on: Arc::new(RwLock::new(
+                    on.into_iter()
+                        .map(|(event, callback)| (event, Arc::new(Mutex::new(callback))))
+                        .collect(),
+                )),
+                on_any: Arc::new(RwLock::new(
+                    on_any.map(|on_any| Arc::new(Mutex::new(on_any))),
+                )),
+                on_packet: Arc::new(RwLock::new(
+                    on_packet.map(|on_packet| Arc::new(Mutex::new(on_packet))),
+                )),
                 outstanding_acks: Arc::new(RwLock::new(Vec::new())),
+                ack_ids: Arc::new(Mutex::new(AckIdAllocator::new(i32::MAX))),
+                max_pending_acks,
+                connect_ack: Arc::new((Mutex::new(None), Condvar::new())),
                 auth,
                 backoff: ExponentialBackoff::default(),
             })),
@@ -94,6 +150,9 @@ impl Client {
 
         let auth = inner.auth.as_ref().map(|data| data.to_string());
 
+        // the answer to a previous connect must not satisfy this one
+        *inner.connect_ack.0.lock()? = None;
+
         // construct the opening packet
         let open_packet = Packet::new(PacketId::Connect, inner.nsp.clone(), auth, None, 0, None);
 
@@ -106,11 +165,25 @@ impl Client {
         Ok(())
     }
 
+    /// Blocks until the server answered the namespace connect request or `timeout`
+    /// elapsed. Fails if the server rejected the namespace.
+    pub(crate) fn wait_for_connect(&self, timeout: Duration) -> Result<()> {
+        let connect_ack = self.inner.read()?.connect_ack.clone();
+        let (ack, condvar) = &*connect_ack;
+
+        let (ack, _) = condvar.wait_timeout_while(ack.lock()?, timeout, |ack| ack.is_none())?;
+        match &*ack {
+            Some(Ok(())) => Ok(()),
+            Some(Err(message)) => Err(Error::InvalidNamespaceConnect(message.clone())),
+            None => Err(Error::IncompleteNamespaceConnect()),
+        }
+    }
+
     /// Sends a message to the server using the underlying `engine.io` protocol.
     /// This message takes an event, which could either be one of the common
     /// events like "message" or "error" or a custom event like "foo". But be
-    /// careful, the data string needs to be valid JSON. It's recommended to use
-    /// a library like `serde_json` to serialize the data properly.
+    /// careful, the data string needs to be valid JSON. For structured data it's
+    /// recommended to send a [`Payload::Value`], which is serialized only once.
     ///
     /// # Example
     /// ```
@@ -147,6 +220,87 @@ impl Client {
         socket.emit(&inner.nsp, event.into(), data.into())
     }
 
+    /// Sends an event without any data to the server, the same as calling
+    /// `socket.emit("foo")` in the javascript client. A handler registered for
+    /// such an event on the receiving side is called with an empty list `[]` as
+    /// its payload.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_socketio::{ClientBuilder, Client, Payload};
+    ///
+    /// let mut socket = ClientBuilder::new("http://localhost:4200/")
+    ///     .connect()
+    ///     .expect("connection failed");
+    ///
+    /// let result = socket.emit_event("ping");
+    ///
+    /// assert!(result.is_ok());
+    /// ```
+    #[inline]
+    pub fn emit_event<E>(&self, event: E) -> Result<()>
+    where
+        E: Into<Event>,
+    {
+        let inner = self.inner.read()?;
+        let socket = inner
+            .socket
+            .clone()
+            .ok_or(Error::IllegalActionBeforeOpen())?;
+        let socket = socket.read()?;
+
+        socket.emit_event(&inner.nsp, event.into())
+    }
+
+    /// Sends a [`Packet`] exactly as it is, including its attachments, e.g. to relay
+    /// a packet received via [`crate::ClientBuilder::on_packet`]. Neither its
+    /// namespace nor its ack id are adjusted to this client. The packet is rejected
+    /// if its attachments don't match its `attachment_count` or placeholders.
+    pub fn send_packet(&self, packet: Packet) -> Result<()> {
+        packet.check_attachments()?;
+        let inner = self.inner.read()?;
+        let socket = inner
+            .socket
+            .clone()
+            .ok_or(Error::IllegalActionBeforeOpen())?;
+        let socket = socket.read()?;
+
+        socket.send(packet)
+    }
+
+    /// Removes the callback registered for `event`, later packets for it aren't
+    /// dispatched to it anymore. A callback that is running right now isn't
+    /// interrupted. This may be called from within a callback, e.g. to handle an
+    /// event only once.
+    /// # Example
+    /// ```rust
+    /// use rust_socketio::{ClientBuilder, Payload};
+    ///
+    /// let socket = ClientBuilder::new("http://localhost:4200/")
+    ///     .on("test", |payload: Payload, _| println!("Received: {:#?}", payload))
+    ///     .connect()
+    ///     .expect("connection failed");
+    ///
+    /// // "test" events are ignored from now on
+    /// socket.off("test").expect("callbacks poisoned");
+    /// ```
+    pub fn off<E: Into<Event>>(&self, event: E) -> Result<()> {
+        let inner = self.inner.read()?;
+        inner.on.write()?.remove(&event.into());
+        Ok(())
+    }
+
+    /// Removes all registered callbacks, including the ones passed to `on_any` and
+    /// `on_packet`.
+    /// The same restrictions as for [`Client::off`] apply.
+    pub fn off_all(&self) -> Result<()> {
+        let inner = self.inner.read()?;
+        inner.on.write()?.clear();
+        *inner.on_any.write()? = None;
+        *inner.on_packet.write()? = None;
+        Ok(())
+    }
+
     /// Disconnects this client from the server by sending a `socket.io` closing
     /// packet.
     /// # Example
@@ -195,11 +349,15 @@ impl Client {
     /// could either be one of the common events like "message" or "error" or a
     /// custom event like "foo", as well as a data parameter. But be careful,
     /// in case you send a [`Payload::String`], the string needs to be valid JSON.
-    /// It's even recommended to use a library like serde_json to serialize the data properly.
+    /// For structured data it's recommended to send a [`Payload::Value`] instead.
     /// It also requires a timeout `Duration` in which the client needs to answer.
     /// If the ack is acked in the correct time span, the specified callback is
     /// called. The callback consumes a [`Payload`] which represents the data send
-    /// by the server.
+    /// by the server: all values acked by the server as a JSON array, followed by
+    /// one call per binary attachment.
+    /// If a maximum of pending acks is configured via
+    /// [`crate::ClientBuilder::max_pending_acks`] and reached, the message isn't
+    /// sent and [`Error::IllegalPendingAcks`] is returned.
     ///
     /// # Example
     /// ```
@@ -217,6 +375,7 @@ impl Client {
     ///     match message {
     ///         Payload::String(str) => println!("{}", str),
     ///         Payload::Binary(bytes) => println!("Received bytes: {:#?}", bytes),
+    ///         _ => {}
     ///    }    
     /// };
     ///
@@ -245,9 +404,11 @@ impl Client {
             .ok_or(Error::IllegalActionBeforeOpen())?;
         let socket = socket.read()?;
 
-        let id = thread_rng().gen_range(0..999);
+        let mut outstanding_acks = inner.outstanding_acks.write()?;
+        let id = Self::next_ack_id(&inner, &mut outstanding_acks)?;
+
         let socket_packet =
-            socket.build_packet_for_payload(data.into(), event.into(), &inner.nsp, Some(id))?;
+            InnerSocket::build_packet_for_payload(data.into(), event.into(), &inner.nsp, Some(id))?;
 
         let ack = Ack {
             id,
@@ -257,13 +418,83 @@ impl Client {
         };
 
         // add the ack to the tuple of outstanding acks
-        inner.outstanding_acks.write()?.push(ack);
+        outstanding_acks.push(ack);
+        drop(outstanding_acks);
 
         socket.send(socket_packet)?;
 
         Ok(())
     }
 
+    /// Drops the acks that timed out and returns the id for a new ack, unless the
+    /// maximum of pending acks is reached.
+    fn next_ack_id(inner: &Inner, outstanding_acks: &mut Vec<Ack>) -> Result<i32> {
+        // acks that timed out won't be called anymore, free up their ids
+        outstanding_acks.retain(|ack| ack.time_started.elapsed() < ack.timeout);
+
+        if let Some(max_pending_acks) = inner.max_pending_acks {
+            if outstanding_acks.len() >= max_pending_acks {
+                return Err(Error::IllegalPendingAcks(max_pending_acks));
+            }
+        }
+
+        inner.ack_ids.lock()?.allocate(outstanding_acks)
+    }
+
+    /// Sends a message like [`Client::emit_with_ack`], but blocks until the server
+    /// acked it and deserializes the first acked value into `T`. Fails with
+    /// [`Error::IncompleteAck`] if there was no ack within `timeout`, and with
+    /// [`Error::InvalidJson`] if the value doesn't deserialize into `T`. As the
+    /// acks are handled by the polling thread, this must not be called from
+    /// within a callback.
+    /// # Example
+    /// ```no_run
+    /// use rust_socketio::ClientBuilder;
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Answer {
+    ///     count: u32,
+    /// }
+    ///
+    /// let socket = ClientBuilder::new("http://localhost:4200/")
+    ///     .connect()
+    ///     .expect("connection failed");
+    ///
+    /// let answer: Answer = socket
+    ///     .emit_with_ack_typed("count", json!({"token": 123}), Duration::from_secs(2))
+    ///     .expect("no answer");
+    /// println!("counted {}", answer.count);
+    /// ```
+    pub fn emit_with_ack_typed<T, E, D>(&self, event: E, data: D, timeout: Duration) -> Result<T>
+    where
+        T: DeserializeOwned,
+        E: Into<Event>,
+        D: Into<Payload>,
+    {
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        self.emit_with_ack(event, data, timeout, move |payload: Payload, _| {
+            // only the first payload holds the acked values
+            let _ = tx.try_send(payload);
+        })?;
+
+        match rx.recv_timeout(timeout) {
+            Ok(Payload::String(values)) => {
+                let first = match serde_json::from_str(&values)? {
+                    serde_json::Value::Array(values) => values.into_iter().next(),
+                    value => Some(value),
+                };
+                Ok(serde_json::from_value(
+                    first.unwrap_or(serde_json::Value::Null),
+                )?)
+            }
+            Ok(_) => Err(Error::InvalidPacket()),
+            Err(_) => Err(Error::IncompleteAck()),
+        }
+    }
+
     pub(crate) fn poll(&self) -> Result<Option<Packet>> {
         loop {
             let inner = self.inner.read()?;
@@ -296,63 +527,59 @@ impl Client {
     }
 
     fn callback<P: Into<Payload>>(&self, event: &Event, payload: P) -> Result<()> {
-        let inner = self.inner.read()?;
-        let mut on = inner.on.write()?;
-        let mut on_any = inner.on_any.write()?;
-        let lock = on.deref_mut();
-        let on_any_lock = on_any.deref_mut();
+        // the callbacks are taken out of their collections before they're called,
+        // so they're free to remove themselves or others
+        let (callback, on_any) = {
+            let inner = self.inner.read()?;
+            let callback = inner.on.read()?.get(event).cloned();
+            let on_any = match event {
+                Event::Message | Event::Custom(_) => inner.on_any.read()?.clone(),
+                _ => None,
+            };
+            (callback, on_any)
+        };
 
         let payload = payload.into();
 
-        if let Some(callback) = lock.get_mut(event) {
-            callback(payload.clone(), self.clone());
+        if let Some(callback) = callback {
+            (**callback.lock()?)(payload.clone(), self.clone());
         }
-        match event {
-            Event::Message | Event::Custom(_) => {
-                if let Some(callback) = on_any_lock {
-                    callback(event.clone(), payload, self.clone())
-                }
-            }
-            _ => {}
+        if let Some(callback) = on_any {
+            (**callback.lock()?)(event.clone(), payload, self.clone());
         }
-        drop(on);
-        drop(on_any);
         Ok(())
     }
 
     /// Handles the incoming acks and classifies what callbacks to call and how.
     #[inline]
     fn handle_ack(&self, socket_packet: &Packet) -> Result<()> {
-        let mut to_be_removed = Vec::new();
         let inner = self.inner.read()?;
         if let Some(id) = socket_packet.id {
-            for (index, ack) in inner.outstanding_acks.write()?.iter_mut().enumerate() {
+            let mut outstanding_acks = inner.outstanding_acks.write()?;
+            for ack in outstanding_acks.iter_mut() {
                 if ack.id == id {
-                    to_be_removed.push(index);
-
                     if ack.time_started.elapsed() < ack.timeout {
-                        if let Some(ref payload) = socket_packet.data {
-                            ack.callback.deref_mut()(
-                                Payload::String(payload.to_owned()),
-                                self.clone(),
-                            );
+                        // the placeholders are left out of the values of a binary ack,
+                        // every attachment is handed to the callback on its own instead
+                        let payload = match socket_packet.packet_type {
+                            PacketId::BinaryAck => Some(socket_packet.arguments())
+                                .filter(|values| !values.is_empty())
+                                .map(|values| serde_json::Value::Array(values).to_string()),
+                            _ => socket_packet.data.clone(),
+                        };
+                        if let Some(payload) = payload {
+                            ack.callback.deref_mut()(Payload::String(payload), self.clone());
                         }
-                        if let Some(ref attachments) = socket_packet.attachments {
-                            if let Some(payload) = attachments.get(0) {
-                                ack.callback.deref_mut()(
-                                    Payload::Binary(payload.to_owned()),
-                                    self.clone(),
-                                );
-                            }
+                        for payload in socket_packet.placeholder_attachments() {
+                            ack.callback.deref_mut()(Payload::Binary(payload), self.clone());
                         }
                     } else {
                         // Do something with timed out acks?
                     }
                 }
             }
-            for index in to_be_removed {
-                inner.outstanding_acks.write()?.remove(index);
-            }
+            // timed out acks are dropped as well, they won't be called anymore
+            outstanding_acks.retain(|ack| ack.id != id && ack.time_started.elapsed() < ack.timeout);
         }
         Ok(())
     }
@@ -360,16 +587,13 @@ impl Client {
     /// Handles a binary event.
     #[inline]
     fn handle_binary_event(&self, packet: &Packet) -> Result<()> {
-        let event = if let Some(string_data) = &packet.data {
-            string_data.replace('\"', "").into()
-        } else {
-            Event::Message
+        let event = match packet.arguments().first() {
+            Some(serde_json::Value::String(name)) => name.as_str().into(),
+            _ => Event::Message,
         };
 
-        if let Some(attachments) = &packet.attachments {
-            if let Some(binary_payload) = attachments.get(0) {
-                self.callback(&event, Payload::Binary(binary_payload.to_owned()))?;
-            }
+        if let Some(binary_payload) = packet.placeholder_attachments().into_iter().next() {
+            self.callback(&event, Payload::Binary(binary_payload))?;
         }
         Ok(())
     }
@@ -384,6 +608,11 @@ impl Client {
             if let Ok(serde_json::Value::Array(contents)) =
                 serde_json::from_str::<serde_json::Value>(data)
             {
+                // an event without arguments gets an empty list of them as its payload
+                if let [serde_json::Value::String(name)] = contents.as_slice() {
+                    return self.callback(&Event::from(name.as_str()), "[]");
+                }
+
                 let event: Event = if contents.len() > 1 {
                     contents
                         .get(0)
@@ -415,6 +644,11 @@ impl Client {
     fn handle_socketio_packet(&self, packet: &Packet) -> Result<()> {
         let inner = self.inner.read()?;
         if packet.nsp == inner.nsp {
+            let on_packet = inner.on_packet.read()?.clone();
+            if let Some(callback) = on_packet {
+                (**callback.lock()?)(packet.clone(), self.clone());
+            }
+
             match packet.packet_type {
                 PacketId::Ack | PacketId::BinaryAck => {
                     if let Err(err) = self.handle_ack(packet) {
@@ -428,19 +662,21 @@ impl Client {
                     }
                 }
                 PacketId::Connect => {
+                    self.connect_acked(&inner, Ok(()))?;
                     self.callback(&Event::Connect, "")?;
                 }
                 PacketId::Disconnect => {
                     self.callback(&Event::Close, "")?;
                 }
                 PacketId::ConnectError => {
+                    let message = packet
+                        .clone()
+                        .data
+                        .unwrap_or_else(|| String::from("\"No error message provided\""));
+                    self.connect_acked(&inner, Err(message.clone()))?;
                     self.callback(
                         &Event::Error,
-                        String::from("Received an ConnectError frame: ")
-                            + &packet
-                                .clone()
-                                .data
-                                .unwrap_or_else(|| String::from("\"No error message provided\"")),
+                        String::from("Received an ConnectError frame: ") + &message,
                     )?;
                 }
                 PacketId::Event => {
@@ -453,6 +689,15 @@ impl Client {
         Ok(())
     }
 
+    /// Stores the server's answer to the namespace connect request and wakes up
+    /// everyone waiting for it.
+    fn connect_acked(&self, inner: &Inner, ack: std::result::Result<(), String>) -> Result<()> {
+        let (state, condvar) = &*inner.connect_ack;
+        *state.lock()? = Some(ack);
+        condvar.notify_all();
+        Ok(())
+    }
+
     fn poll_callback(&self) {
         let self_clone = self.clone();
         // Use thread to consume items in iterator in order to call callbacks
@@ -512,6 +757,8 @@ impl<'a> Iterator for Iter<'a> {
 
 #[cfg(test)]
 mod test {
+    use std::convert::TryFrom;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::mpsc;
     use std::sync::mpsc::Receiver;
     use std::thread::sleep;
@@ -531,6 +778,7 @@ mod test {
             .on("test", |msg, _| match msg {
                 Payload::String(str) => println!("Received string: {}", str),
                 Payload::Binary(bin) => println!("Received binary data: {:#?}", bin),
+                _ => {}
             })
             .connect()?;
 
@@ -568,6 +816,88 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn socket_io_connect_with_timeout_integration() -> Result<()> {
+        let url = crate::test::socket_io_server();
+
+        let socket = ClientBuilder::new(url)
+            .namespace("/admin")
+            .connect_with_timeout(Duration::from_secs(5))?;
+
+        // the namespace is joined, so the emit can't race the connect ack
+        assert!(socket.emit("test", json!({"joined": true})).is_ok());
+        assert!(socket.disconnect().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn socket_io_typed_ack_integration() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Answer {
+            name: String,
+            count: u32,
+        }
+
+        let url = crate::test::socket_io_server();
+        let socket = ClientBuilder::new(url).connect()?;
+
+        let answer: Answer =
+            socket.emit_with_ack_typed("typed-ack", json!({}), Duration::from_secs(5))?;
+        assert_eq!(
+            answer,
+            Answer {
+                name: "woot".to_owned(),
+                count: 2
+            }
+        );
+
+        // the answer doesn't fit, which isn't reported as a timeout
+        let result =
+            socket.emit_with_ack_typed::<u32, _, _>("typed-ack", json!({}), Duration::from_secs(5));
+        assert!(matches!(result, Err(Error::InvalidJson(_))));
+
+        assert!(socket.disconnect().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn socket_io_relay_integration() -> Result<()> {
+        let url = crate::test::socket_io_server();
+
+        let (tx, rx) = mpsc::sync_channel(1);
+        let target = ClientBuilder::new(url.clone())
+            .on("test-received", move |payload, _| {
+                let _ = tx.try_send(payload);
+            })
+            .connect()?;
+
+        // relays the server's greeting on the "test" event to the other socket, the
+        // server then confirms it to that socket
+        let relay_target = target.clone();
+        let source = ClientBuilder::new(url)
+            .on_packet(move |packet, _| {
+                if packet.packet_type == PacketId::Event
+                    && packet.data.as_deref() == Some("[\"test\",\"Hello from the test event!\"]")
+                {
+                    relay_target.send_packet(packet).unwrap();
+                }
+            })
+            .connect()?;
+
+        let payload = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(
+            payload,
+            Payload::String("\"Hello from the test event!\"".to_owned())
+        );
+
+        assert!(source.disconnect().is_ok());
+        assert!(target.disconnect().is_ok());
+
+        Ok(())
+    }
+
     #[test]
     fn socket_io_reconnect_integration() -> Result<()> {
         let url = crate::test::socket_io_restart_server();
@@ -606,6 +936,25 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn socket_io_custom_path_integration() -> Result<()> {
+        // the test server is mounted at `/custom/` as well
+        let (tx, rx) = mpsc::channel();
+        let socket = ClientBuilder::new(crate::test::socket_io_server())
+            .path(String::from("/custom/"))
+            .on("test", move |payload, _| {
+                let _ = tx.send(payload);
+            })
+            .connect()?;
+
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(2)).unwrap(),
+            Payload::String("\"Hello from the test event!\"".to_owned())
+        );
+
+        socket.disconnect()
+    }
+
     #[test]
     fn socket_io_builder_integration() -> Result<()> {
         let url = crate::test::socket_io_server();
@@ -647,6 +996,48 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn socket_io_concurrent_binary_emit_integration() -> Result<()> {
+        let url = crate::test::socket_io_server();
+        let (tx, rx) = mpsc::channel();
+
+        let socket = ClientBuilder::new(url)
+            .on("binary-received", move |payload, _| {
+                if let Payload::Binary(bin) = payload {
+                    tx.send(bin).unwrap();
+                }
+            })
+            .connect()?;
+
+        let handles: Vec<_> = (0..16u8)
+            .map(|i| {
+                let socket = socket.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..8 {
+                        socket
+                            .emit("binary", Bytes::from(vec![i; 4]))
+                            .expect("emit failed");
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut received = [0; 16];
+        for _ in 0..16 * 8 {
+            let bin = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            // every echoed attachment must be one of the payloads we sent, intact
+            assert_eq!(bin.len(), 4);
+            assert!(bin.iter().all(|byte| *byte == bin[0]));
+            received[bin[0] as usize] += 1;
+        }
+        assert!(received.iter().all(|count| *count == 8));
+
+        socket.disconnect()
+    }
+
     #[test]
     fn socket_io_builder_integration_iterator() -> Result<()> {
         let url = crate::test::socket_io_server();
@@ -738,8 +1129,8 @@ mod test {
         let (event, payload) = rx.recv().unwrap();
         assert_eq!(event, "auth".to_owned());
         match payload {
-            Payload::Binary(_) => assert!(false),
             Payload::String(p) => assert_eq!(p, "\"success\"".to_owned()),
+            _ => assert!(false),
         };
 
         Ok(())
@@ -811,29 +1202,29 @@ mod test {
         let (event, payload) = rx.recv().unwrap();
         assert_eq!(event, "message".to_owned());
         match payload {
-            Payload::Binary(_) => assert!(false),
             Payload::String(p) => assert_eq!(p, "\"Hello from the message event!\"".to_owned()),
+            _ => assert!(false),
         };
 
         let (event, payload) = rx.recv().unwrap();
         assert_eq!(event, "test".to_owned());
         match payload {
-            Payload::Binary(_) => assert!(false),
             Payload::String(p) => assert_eq!(p, "\"Hello from the test event!\"".to_owned()),
+            _ => assert!(false),
         };
 
         let (event, payload) = rx.recv().unwrap();
         assert_eq!(event, "message".to_owned());
         match payload {
             Payload::Binary(b) => assert_eq!(b, Bytes::from_static(&[4, 5, 6])),
-            Payload::String(_) => assert!(false),
+            _ => assert!(false),
         };
 
         let (event, payload) = rx.recv().unwrap();
         assert_eq!(event, "test".to_owned());
         match payload {
             Payload::Binary(b) => assert_eq!(b, Bytes::from_static(&[1, 2, 3])),
-            Payload::String(_) => assert!(false),
+            _ => assert!(false),
         };
 
         assert!(socket
@@ -854,5 +1245,353 @@ mod test {
         Ok(())
     }
 
+    /// Creates a client that isn't connected, packets are handed to it directly.
+    fn test_client(configure: impl FnOnce(ClientBuilder) -> ClientBuilder) -> Result<Client> {
+        configure(ClientBuilder::new("http://localhost:4200")).build()
+    }
+
+    fn client_with_ack(nsp: &str, id: i32, acked: Arc<AtomicUsize>) -> Result<Client> {
+        let client = test_client(|builder| builder.namespace(nsp))?;
+        client.inner.read()?.outstanding_acks.write()?.push(Ack {
+            id,
+            timeout: Duration::from_secs(10),
+            time_started: Instant::now(),
+            callback: Callback::<SocketCallback>::new(move |_, _| {
+                acked.fetch_add(1, Ordering::SeqCst);
+            }),
+        });
+        Ok(client)
+    }
+
+    #[test]
+    fn test_ack_removes_every_match() -> Result<()> {
+        let acked = Arc::new(AtomicUsize::new(0));
+        let client = client_with_ack("/", 1, acked.clone())?;
+        for id in [1, 2] {
+            let acked = acked.clone();
+            client.inner.read()?.outstanding_acks.write()?.push(Ack {
+                id,
+                timeout: Duration::from_secs(10),
+                time_started: Instant::now(),
+                callback: Callback::<SocketCallback>::new(move |_, _| {
+                    acked.fetch_add(1, Ordering::SeqCst);
+                }),
+            });
+        }
+
+        client.handle_socketio_packet(&Packet::try_from(&Bytes::from_static(b"31[\"woot\"]"))?)?;
+
+        // both acks with the id are resolved and removed, the other one is kept
+        assert_eq!(acked.load(Ordering::SeqCst), 2);
+        let inner = client.inner.read()?;
+        let outstanding_acks = inner.outstanding_acks.read()?;
+        assert_eq!(
+            outstanding_acks
+                .iter()
+                .map(|ack| ack.id)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ack_namespace_routing() -> Result<()> {
+        let default_acked = Arc::new(AtomicUsize::new(0));
+        let admin_acked = Arc::new(AtomicUsize::new(0));
+        let default_client = client_with_ack("/", 456, default_acked.clone())?;
+        let admin_client = client_with_ack("/admin", 456, admin_acked.clone())?;
+
+        let admin_ack = Packet::try_from(&Bytes::from_static(b"3/admin,456[\"woot\"]"))?;
+        default_client.handle_socketio_packet(&admin_ack)?;
+        admin_client.handle_socketio_packet(&admin_ack)?;
+
+        assert_eq!(default_acked.load(Ordering::SeqCst), 0);
+        assert_eq!(admin_acked.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            default_client.inner.read()?.outstanding_acks.read()?.len(),
+            1
+        );
+        assert!(admin_client
+            .inner
+            .read()?
+            .outstanding_acks
+            .read()?
+            .is_empty());
+
+        let default_ack = Packet::try_from(&Bytes::from_static(b"3456[\"woot\"]"))?;
+        default_client.handle_socketio_packet(&default_ack)?;
+        admin_client.handle_socketio_packet(&default_ack)?;
+
+        assert_eq!(default_acked.load(Ordering::SeqCst), 1);
+        assert_eq!(admin_acked.load(Ordering::SeqCst), 1);
+        assert!(default_client
+            .inner
+            .read()?
+            .outstanding_acks
+            .read()?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_for_connect() -> Result<()> {
+        let client = test_client(|builder| builder.namespace("/admin"))?;
+
+        assert!(matches!(
+            client.wait_for_connect(Duration::from_millis(50)),
+            Err(Error::IncompleteNamespaceConnect())
+        ));
+
+        let acking_client = client.clone();
+        let ack = std::thread::spawn(move || -> Result<()> {
+            std::thread::sleep(Duration::from_millis(50));
+            let packet = Packet::try_from(&Bytes::from_static(b"0/admin,{\"sid\":\"1\"}"))?;
+            acking_client.handle_socketio_packet(&packet)
+        });
+        client.wait_for_connect(Duration::from_secs(5))?;
+        ack.join().unwrap()?;
+
+        let packet = Packet::try_from(&Bytes::from_static(
+            b"4/admin,{\"message\":\"Not authorized\"}",
+        ))?;
+        client.handle_socketio_packet(&packet)?;
+        assert!(matches!(
+            client.wait_for_connect(Duration::from_secs(5)),
+            Err(Error::InvalidNamespaceConnect(message)) if message.contains("Not authorized")
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_off() -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let client = test_client(|mut builder| {
+            for event in ["test", "other"] {
+                let tx = tx.clone();
+                builder = builder.on(event, move |_, _| tx.send(event).unwrap());
+            }
+            builder.on_any(move |_, _, _| tx.send("any").unwrap())
+        })?;
+        let test = Packet::try_from(&Bytes::from_static(b"2[\"test\",1]"))?;
+        let other = Packet::try_from(&Bytes::from_static(b"2[\"other\",1]"))?;
+
+        client.off("test")?;
+        client.handle_socketio_packet(&test)?;
+        client.handle_socketio_packet(&other)?;
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec!["any", "other", "any"]
+        );
+
+        client.off_all()?;
+        client.handle_socketio_packet(&test)?;
+        client.handle_socketio_packet(&other)?;
+        assert!(rx.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_off_within_callback() -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let client = test_client(|builder| {
+            builder
+                .on("once", move |_, client: Client| {
+                    client.off("once").unwrap();
+                    tx.send(()).unwrap();
+                })
+                .on_any(|_, _, client: Client| client.off_all().unwrap())
+        })?;
+
+        let once = Packet::try_from(&Bytes::from_static(b"2[\"once\",1]"))?;
+        client.handle_socketio_packet(&once)?;
+        client.handle_socketio_packet(&once)?;
+        assert_eq!(rx.try_iter().count(), 1);
+        assert!(client.inner.read()?.on_any.read()?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ack_with_multiple_values() -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let client = test_client(|builder| builder)?;
+        client.inner.read()?.outstanding_acks.write()?.push(Ack {
+            id: 1,
+            timeout: Duration::from_secs(5),
+            time_started: Instant::now(),
+            callback: Callback::<SocketCallback>::new(move |payload, _| tx.send(payload).unwrap()),
+        });
+
+        let mut packet = Packet::try_from(&Bytes::from_static(
+            b"62-1[\"result\",{\"ok\":true},{\"_placeholder\":true,\"num\":0},{\"_placeholder\":true,\"num\":1}]",
+        ))?;
+        packet.bind_attachments(vec![Bytes::from_static(b"one"), Bytes::from_static(b"two")])?;
+        client.handle_socketio_packet(&packet)?;
+
+        let payloads = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(payloads.len(), 3);
+        match &payloads[0] {
+            Payload::String(values) => assert_eq!(
+                serde_json::from_str::<serde_json::Value>(values)?,
+                json!(["result", {"ok": true}])
+            ),
+            _ => panic!("expected the values of the ack"),
+        }
+        assert_eq!(payloads[1], Payload::Binary(Bytes::from_static(b"one")));
+        assert_eq!(payloads[2], Payload::Binary(Bytes::from_static(b"two")));
+        assert!(client.inner.read()?.outstanding_acks.read()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_on_packet() -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let client = test_client(|builder| {
+            builder
+                .namespace("/admin")
+                .on_packet(move |packet, _| tx.send(packet).unwrap())
+        })?;
+
+        let raw = Bytes::from_static(b"51-/admin,7[\"relay\",{\"_placeholder\":true,\"num\":0}]");
+        let mut packet = Packet::try_from(&raw)?;
+        packet.bind_attachments(vec![Bytes::from_static(b"\x01\x02")])?;
+        client.handle_socketio_packet(&packet)?;
+        // packets of other namespaces aren't ours to relay
+        client.handle_socketio_packet(&Packet::try_from(&Bytes::from_static(b"2[\"x\"]"))?)?;
+
+        let relayed = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(relayed, vec![packet]);
+        assert_eq!(Bytes::from(&relayed[0]), raw);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_pending_acks() -> Result<()> {
+        let client = test_client(|builder| builder.max_pending_acks(2))?;
+        let inner = client.inner.read()?;
+        let mut outstanding_acks = Vec::new();
+        let ack = |id, timeout| Ack {
+            id,
+            timeout,
+            time_started: Instant::now(),
+            callback: Callback::<SocketCallback>::new(|_, _| {}),
+        };
+
+        for _ in 0..2 {
+            let id = Client::next_ack_id(&inner, &mut outstanding_acks)?;
+            outstanding_acks.push(ack(id, Duration::from_secs(60)));
+        }
+        assert!(matches!(
+            Client::next_ack_id(&inner, &mut outstanding_acks),
+            Err(Error::IllegalPendingAcks(2))
+        ));
+
+        // timed out acks don't count towards the limit
+        outstanding_acks[0].timeout = Duration::ZERO;
+        let id = Client::next_ack_id(&inner, &mut outstanding_acks)?;
+        assert_eq!(outstanding_acks.len(), 1);
+        assert_eq!(id, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_connect_error_invalid_namespace() -> Result<()> {
+        let (tx, rx) = mpsc::sync_channel(1);
+        let client = test_client(|builder| {
+            builder
+                .namespace("/nope")
+                .on(Event::Error, move |payload, _| {
+                    if let Payload::String(message) = payload {
+                        tx.send(message).unwrap();
+                    }
+                })
+        })?;
+
+        let packet = Packet::try_from(&Bytes::from_static(
+            b"4/nope,{\"message\":\"Invalid namespace\"}",
+        ))?;
+        client.handle_socketio_packet(&packet)?;
+
+        let message = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(message.starts_with("Received an ConnectError frame: "));
+        assert!(message.contains("Invalid namespace"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_without_arguments() -> Result<()> {
+        let (tx, rx) = mpsc::sync_channel(3);
+        let any_tx = tx.clone();
+        let client = test_client(|builder| {
+            builder
+                .on("ping", move |payload, _| {
+                    tx.send((Event::from("ping"), payload)).unwrap();
+                })
+                .on_any(move |event, payload, _| {
+                    any_tx.send((event, payload)).unwrap();
+                })
+        })?;
+
+        let packet = Packet::try_from(&Bytes::from_static(b"2[\"ping\"]"))?;
+        client.handle_socketio_packet(&packet)?;
+        let empty = Payload::String(String::from("[]"));
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                (Event::from("ping"), empty.clone()),
+                (Event::from("ping"), empty.clone())
+            ]
+        );
+
+        // the event is the same without a handler registered for it
+        let packet = Packet::try_from(&Bytes::from_static(b"2[\"hello\"]"))?;
+        client.handle_socketio_packet(&packet)?;
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![(Event::from("hello"), empty)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ack_id_allocator() -> Result<()> {
+        let ack = |id| Ack {
+            id,
+            timeout: Duration::from_secs(10),
+            time_started: Instant::now(),
+            callback: Callback::<SocketCallback>::new(|_, _| {}),
+        };
+        let mut allocator = AckIdAllocator::new(3);
+
+        let mut outstanding = Vec::new();
+        for expected in 0..=3 {
+            let id = allocator.allocate(&outstanding)?;
+            assert_eq!(id, expected);
+            outstanding.push(ack(id));
+        }
+        assert!(matches!(
+            allocator.allocate(&outstanding),
+            Err(Error::IllegalAckIdAllocation())
+        ));
+
+        // only the freed ids are handed out again, after wrapping around
+        outstanding.retain(|ack| ack.id != 2 && ack.id != 0);
+        assert_eq!(allocator.allocate(&outstanding)?, 0);
+        outstanding.push(ack(0));
+        assert_eq!(allocator.allocate(&outstanding)?, 2);
+
+        Ok(())
+    }
+
     // TODO: 0.3.X add secure socketio server
 }
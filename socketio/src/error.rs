@@ -44,6 +44,22 @@ pub enum Error {
     IncompleteResponseFromEngineIo(#[from] rust_engineio::Error),
     #[error("Invalid packet type while reading attachments")]
     InvalidAttachmentPacketType(u8),
+    #[error("Attachment placeholder references a missing attachment: {0}")]
+    InvalidAttachmentPlaceholder(usize),
+    #[error("Packet declares {0} attachments but its data holds {1} placeholders")]
+    InvalidAttachmentCount(u8, usize),
+    #[error("Packet declares {0} attachments but holds {1}")]
+    IllegalAttachmentCount(u8, usize),
+    #[error("All ack ids are taken by outstanding acks")]
+    IllegalAckIdAllocation(),
+    #[error("The maximum of {0} pending acks is reached")]
+    IllegalPendingAcks(usize),
+    #[error("The server did not acknowledge the namespace connection in time")]
+    IncompleteNamespaceConnect(),
+    #[error("The server rejected the namespace connection: {0}")]
+    InvalidNamespaceConnect(String),
+    #[error("The server did not ack in time")]
+    IncompleteAck(),
 }
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
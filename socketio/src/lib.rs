@@ -13,6 +13,7 @@
 //!        match payload {
 //!            Payload::String(str) => println!("Received: {}", str),
 //!            Payload::Binary(bin_data) => println!("Received bytes: {:#?}", bin_data),
+//!            _ => {}
 //!        }
 //!        socket.emit("test", json!({"got ack": true})).expect("Server unreachable")
 //! };
@@ -101,6 +102,8 @@ pub use error::Error;
 
 pub use {event::Event, payload::Payload};
 
+pub use packet::{Packet, PacketId};
+
 pub use client::{Client, ClientBuilder, TransportType};
 
 // TODO: 0.4.0 remove
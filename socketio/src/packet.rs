@@ -1,7 +1,6 @@
 use crate::error::{Error, Result};
 use byte::{ctx::Str, BytesExt};
 use bytes::{BufMut, Bytes, BytesMut};
-use regex::Regex;
 use std::convert::TryFrom;
 
 /// An enumeration of the different `Packet` types in the `socket.io` protocol.
@@ -25,6 +24,9 @@ pub struct Packet {
     pub id: Option<i32>,
     pub attachment_count: u8,
     pub attachments: Option<Vec<Bytes>>,
+    /// The `num` of each attachment placeholder in the data of a received binary
+    /// packet, which keeps the placeholders where they were sent.
+    pub placeholders: Vec<usize>,
 }
 
 impl TryFrom<u8> for PacketId {
@@ -60,8 +62,87 @@ impl Packet {
             id,
             attachment_count,
             attachments,
+            placeholders: Vec::new(),
         }
     }
+
+    /// Stores the received attachments, which are expected in the order of their
+    /// `num`, after checking that the placeholders in the data match them.
+    pub(crate) fn bind_attachments(&mut self, attachments: Vec<Bytes>) -> Result<()> {
+        self.check_placeholders(attachments.len())?;
+        self.attachments = Some(attachments);
+
+        Ok(())
+    }
+
+    /// Checks that the attachments match the declared `attachment_count` as well as
+    /// the placeholders in the data, if there are any, so the packet is sent as
+    /// well formed frames.
+    pub(crate) fn check_attachments(&self) -> Result<()> {
+        let attachments = self.attachments.as_ref().map_or(0, Vec::len);
+        let is_binary = matches!(
+            self.packet_type,
+            PacketId::BinaryAck | PacketId::BinaryEvent
+        );
+        if attachments != self.attachment_count as usize || (!is_binary && attachments > 0) {
+            return Err(Error::IllegalAttachmentCount(
+                self.attachment_count,
+                attachments,
+            ));
+        }
+        if !self.placeholders.is_empty() {
+            self.check_placeholders(attachments)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_placeholders(&self, attachments: usize) -> Result<()> {
+        // reading more or fewer attachments than referenced would bind the wrong bytes
+        if self.placeholders.len() != self.attachment_count as usize {
+            return Err(Error::InvalidAttachmentCount(
+                self.attachment_count,
+                self.placeholders.len(),
+            ));
+        }
+        if let Some(num) = self.placeholders.iter().find(|num| **num >= attachments) {
+            return Err(Error::InvalidAttachmentPlaceholder(*num));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the top level values of the data that aren't attachment placeholders.
+    pub(crate) fn arguments(&self) -> Vec<serde_json::Value> {
+        match self
+            .data
+            .as_deref()
+            .map(serde_json::from_str::<serde_json::Value>)
+        {
+            Some(Ok(serde_json::Value::Array(values))) => values
+                .into_iter()
+                .filter(|value| placeholder_num(value).is_none())
+                .collect(),
+            Some(Ok(value)) => vec![value],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the attachments in the order their placeholders appear in the data.
+    pub(crate) fn placeholder_attachments(&self) -> Vec<Bytes> {
+        let attachments = match self.attachments.as_ref() {
+            Some(attachments) => attachments,
+            None => return Vec::new(),
+        };
+        if self.placeholders.is_empty() {
+            return attachments.clone();
+        }
+
+        self.placeholders
+            .iter()
+            .filter_map(|num| attachments.get(*num).cloned())
+            .collect()
+    }
 }
 
 impl From<Packet> for Bytes {
@@ -98,19 +179,17 @@ impl From<&Packet> for Bytes {
 
         let mut buffer = BytesMut::new();
         buffer.put(string.as_ref());
-        if packet.attachments.as_ref().is_some() {
-            // check if an event type is present
+        if packet.attachments.is_some() && packet.placeholders.is_empty() {
+            // one placeholder per attachment, following the event type if present,
+            // the data of a received packet holds its placeholders already
+            let placeholders = (0..packet.attachment_count)
+                .map(|num| format!("{{\"_placeholder\":true,\"num\":{}}}", num))
+                .collect::<Vec<_>>()
+                .join(",");
             let placeholder = if let Some(event_type) = packet.data.as_ref() {
-                format!(
-                    "[{},{{\"_placeholder\":true,\"num\":{}}}]",
-                    event_type,
-                    packet.attachment_count - 1,
-                )
+                format!("[{},{}]", event_type, placeholders)
             } else {
-                format!(
-                    "[{{\"_placeholder\":true,\"num\":{}}}]",
-                    packet.attachment_count - 1,
-                )
+                format!("[{}]", placeholders)
             };
 
             // build the buffers
@@ -160,7 +239,8 @@ impl TryFrom<&Bytes> for Packet {
             0
         };
 
-        let nsp: &str = if payload.get(i + 1).ok_or(Error::IncompletePacket())? == &b'/' {
+        // a packet may consist of its type only, e.g. `1` to disconnect from `/`
+        let nsp: &str = if payload.get(i + 1) == Some(&b'/') {
             let mut start = i + 1;
             while payload.get(i).ok_or(Error::IncompletePacket())? != &b',' && i < payload.len() {
                 i += 1;
@@ -196,71 +276,62 @@ impl TryFrom<&Bytes> for Packet {
             None
         };
 
+        let mut placeholders = Vec::new();
         let data = if payload.get(i + 1).is_some() {
             let start = if id.is_some() { i } else { i + 1 };
 
-            let mut json_data = serde_json::Value::Null;
-
-            let mut end = payload.len();
-            while serde_json::from_str::<serde_json::Value>(
-                &payload
-                    .iter()
-                    .skip(start)
-                    .take(end - start)
-                    .map(|byte| *byte as char)
-                    .collect::<String>(),
-            )
-            .is_err()
-            {
-                end -= 1;
-            }
-
-            if end != start {
-                // unwrapping here is in fact safe as we checked for errors in the
-                // condition of the loop
-                json_data = serde_json::from_str(
-                    &payload
-                        .iter()
-                        .skip(start)
-                        .take(end - start)
-                        .map(|byte| *byte as char)
-                        .collect::<String>(),
-                )
-                .unwrap();
-            }
-
-            match packet_id {
-                PacketId::BinaryAck | PacketId::BinaryEvent => {
-                    let re_close = Regex::new(r",]$|]$").unwrap();
-                    let mut str = json_data
-                        .to_string()
-                        .replace("{\"_placeholder\":true,\"num\":0}", "");
-
-                    if str.starts_with('[') {
-                        str.remove(0);
-                    }
-                    str = re_close.replace(&str, "").to_string();
-
-                    if str.is_empty() {
-                        None
-                    } else {
-                        Some(str)
+            // text packets must be valid utf-8, otherwise they'd silently decode as `null`
+            let text = std::str::from_utf8(&payload[start..])?;
+
+            // parse the first json value of the remaining bytes in a single pass, it's
+            // kept as it was sent and anything trailing it is ignored
+            let mut values = serde_json::Deserializer::from_str(text).into_iter();
+            match values.next() {
+                Some(Ok(json_data)) => {
+                    // the attachments are bound by the `num` of these placeholders
+                    // once they are received
+                    if let PacketId::BinaryAck | PacketId::BinaryEvent = packet_id {
+                        collect_placeholders(&json_data, &mut placeholders);
                     }
+                    Some(text[..values.byte_offset()].trim().to_owned())
                 }
-                _ => Some(json_data.to_string()),
+                _ => Some(serde_json::Value::Null.to_string()),
             }
         } else {
             None
         };
 
-        Ok(Packet::new(
-            packet_id,
-            nsp.to_owned(),
-            data,
-            id,
-            attachment_count,
-            None,
-        ))
+        let mut packet = Packet::new(packet_id, nsp.to_owned(), data, id, attachment_count, None);
+        packet.placeholders = placeholders;
+
+        Ok(packet)
+    }
+}
+
+/// Collects the `num` of every attachment placeholder in `value`, at any depth.
+fn collect_placeholders(value: &serde_json::Value, placeholders: &mut Vec<usize>) {
+    if let Some(num) = placeholder_num(value) {
+        placeholders.push(num);
+        return;
+    }
+    match value {
+        serde_json::Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_placeholders(value, placeholders)),
+        serde_json::Value::Object(object) => object
+            .values()
+            .for_each(|value| collect_placeholders(value, placeholders)),
+        _ => {}
+    }
+}
+
+/// Returns the `num` of a `{"_placeholder":true,"num":<n>}` object.
+fn placeholder_num(value: &serde_json::Value) -> Option<usize> {
+    let object = value.as_object()?;
+    if object.get("_placeholder")?.as_bool()? {
+        object.get("num")?.as_u64().map(|num| num as usize)
+    } else {
+        None
     }
 }
 
@@ -389,14 +460,19 @@ mod test {
         assert!(packet.is_ok());
 
         assert_eq!(
-            Packet::new(
-                PacketId::BinaryEvent,
-                "/".to_owned(),
-                Some(String::from("\"hello\"")),
-                None,
-                1,
-                None,
-            ),
+            Packet {
+                placeholders: vec![0],
+                ..Packet::new(
+                    PacketId::BinaryEvent,
+                    "/".to_owned(),
+                    Some(String::from(
+                        "[\"hello\",{\"_placeholder\":true,\"num\":0}]"
+                    )),
+                    None,
+                    1,
+                    None,
+                )
+            },
             packet.unwrap()
         );
 
@@ -407,14 +483,19 @@ mod test {
         assert!(packet.is_ok());
 
         assert_eq!(
-            Packet::new(
-                PacketId::BinaryEvent,
-                "/admin".to_owned(),
-                Some(String::from("\"project:delete\"")),
-                Some(456),
-                1,
-                None,
-            ),
+            Packet {
+                placeholders: vec![0],
+                ..Packet::new(
+                    PacketId::BinaryEvent,
+                    "/admin".to_owned(),
+                    Some(String::from(
+                        "[\"project:delete\",{\"_placeholder\":true,\"num\":0}]"
+                    )),
+                    Some(456),
+                    1,
+                    None,
+                )
+            },
             packet.unwrap()
         );
 
@@ -423,14 +504,17 @@ mod test {
         assert!(packet.is_ok());
 
         assert_eq!(
-            Packet::new(
-                PacketId::BinaryAck,
-                "/admin".to_owned(),
-                None,
-                Some(456),
-                1,
-                None,
-            ),
+            Packet {
+                placeholders: vec![0],
+                ..Packet::new(
+                    PacketId::BinaryAck,
+                    "/admin".to_owned(),
+                    Some(String::from("[{\"_placeholder\":true,\"num\":0}]")),
+                    Some(456),
+                    1,
+                    None,
+                )
+            },
             packet.unwrap()
         );
     }
@@ -587,6 +671,186 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_decode_large_event() {
+        let items = vec!["\"abcdefghij\""; 100_000].join(",");
+        let payload = Bytes::from(format!("2[\"big\",[{}]]", items));
+        let packet = Packet::try_from(&payload).unwrap();
+
+        assert_eq!(PacketId::Event, packet.packet_type);
+        assert_eq!(Some(format!("[\"big\",[{}]]", items)), packet.data);
+    }
+
+    #[test]
+    fn test_bind_reordered_placeholders() -> Result<()> {
+        let payload = Bytes::from_static(
+            b"52-[\"hello\",{\"_placeholder\":true,\"num\":1},{\"_placeholder\":true,\"num\":0}]",
+        );
+        let mut packet = Packet::try_from(&payload)?;
+        assert_eq!(
+            packet.data,
+            Some(String::from(
+                "[\"hello\",{\"_placeholder\":true,\"num\":1},{\"_placeholder\":true,\"num\":0}]"
+            ))
+        );
+        assert_eq!(packet.placeholders, vec![1, 0]);
+
+        packet.bind_attachments(vec![
+            Bytes::from_static(b"zero"),
+            Bytes::from_static(b"one"),
+        ])?;
+        assert_eq!(
+            packet.placeholder_attachments(),
+            vec![Bytes::from_static(b"one"), Bytes::from_static(b"zero")]
+        );
+
+        // placeholders may be nested within the arguments
+        let mut packet = Packet::try_from(&Bytes::from_static(
+            b"52-[\"x\",{\"buf\":{\"_placeholder\":true,\"num\":1}},[{\"_placeholder\":true,\"num\":0}]]",
+        ))?;
+        assert_eq!(packet.placeholders, vec![1, 0]);
+        packet.bind_attachments(vec![
+            Bytes::from_static(b"zero"),
+            Bytes::from_static(b"one"),
+        ])?;
+        assert_eq!(
+            packet.placeholder_attachments(),
+            vec![Bytes::from_static(b"one"), Bytes::from_static(b"zero")]
+        );
+
+        let mut packet = Packet::try_from(&Bytes::from_static(
+            b"51-[\"hello\",{\"_placeholder\":true,\"num\":3}]",
+        ))?;
+        assert!(matches!(
+            packet.bind_attachments(vec![Bytes::from_static(b"zero")]),
+            Err(Error::InvalidAttachmentPlaceholder(3))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attachment_count_mismatch() -> Result<()> {
+        // too many placeholders
+        let mut packet = Packet::try_from(&Bytes::from_static(
+            b"51-[\"hello\",{\"_placeholder\":true,\"num\":0},{\"_placeholder\":true,\"num\":1}]",
+        ))?;
+        assert!(matches!(
+            packet.bind_attachments(vec![Bytes::from_static(b"zero")]),
+            Err(Error::InvalidAttachmentCount(1, 2))
+        ));
+
+        // too few placeholders
+        let mut packet = Packet::try_from(&Bytes::from_static(
+            b"52-[\"hello\",{\"_placeholder\":true,\"num\":0}]",
+        ))?;
+        assert!(matches!(
+            packet.bind_attachments(vec![
+                Bytes::from_static(b"zero"),
+                Bytes::from_static(b"one")
+            ]),
+            Err(Error::InvalidAttachmentCount(2, 1))
+        ));
+
+        // nested placeholders are counted as well
+        let mut packet = Packet::try_from(&Bytes::from_static(
+            b"51-[\"x\",{\"buf\":{\"_placeholder\":true,\"num\":0}}]",
+        ))?;
+        packet.bind_attachments(vec![Bytes::from_static(b"zero")])?;
+        assert_eq!(packet.placeholders, vec![0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_default_namespace() -> Result<()> {
+        // the default namespace may be sent implicitly or as an explicit `/,`
+        for payload in [&b"2[\"hi\"]"[..], &b"2/,[\"hi\"]"[..]] {
+            let packet = Packet::try_from(&Bytes::from(payload))?;
+            assert_eq!(packet.nsp, "/");
+            assert_eq!(packet.data, Some(String::from("[\"hi\"]")));
+        }
+
+        let packet = Packet::try_from(&Bytes::from_static(b"2/admin,[\"hi\"]"))?;
+        assert_eq!(packet.nsp, "/admin");
+        assert_eq!(packet.data, Some(String::from("[\"hi\"]")));
+
+        for payload in [&b"31[\"hi\"]"[..], &b"3/,1[\"hi\"]"[..]] {
+            let packet = Packet::try_from(&Bytes::from(payload))?;
+            assert_eq!(packet.packet_type, PacketId::Ack);
+            assert_eq!(packet.nsp, "/");
+            assert_eq!(packet.id, Some(1));
+            assert_eq!(packet.data, Some(String::from("[\"hi\"]")));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        let payload = Bytes::from_static(b"2[\"hi\xff\"]");
+        assert!(matches!(
+            Packet::try_from(&payload),
+            Err(Error::InvalidUtf8(_))
+        ));
+
+        let payload = Bytes::from_static(b"2/admin,1[\"\xc3\x28\"]");
+        assert!(matches!(
+            Packet::try_from(&payload),
+            Err(Error::InvalidUtf8(_))
+        ));
+    }
+
+    #[test]
+    fn test_encode_multiple_attachments() -> Result<()> {
+        let raw = Bytes::from_static(
+            b"62-/admin,3[\"ok\",{\"_placeholder\":true,\"num\":0},{\"_placeholder\":true,\"num\":1}]",
+        );
+        let mut packet = Packet::try_from(&raw)?;
+        packet.bind_attachments(vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")])?;
+
+        assert_eq!(Bytes::from(&packet), raw);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_attachments() -> Result<()> {
+        let mut packet = Packet::try_from(&Bytes::from_static(
+            b"51-[\"hello\",{\"_placeholder\":true,\"num\":0}]",
+        ))?;
+        packet.bind_attachments(vec![Bytes::from_static(b"zero")])?;
+        packet.check_attachments()?;
+
+        packet.attachments = None;
+        assert!(matches!(
+            packet.check_attachments(),
+            Err(Error::IllegalAttachmentCount(1, 0))
+        ));
+
+        packet.attachments = Some(vec![Bytes::from_static(b"zero")]);
+        packet.placeholders = vec![1];
+        assert!(matches!(
+            packet.check_attachments(),
+            Err(Error::InvalidAttachmentPlaceholder(1))
+        ));
+
+        let packet = Packet::new(
+            PacketId::Event,
+            "/".to_owned(),
+            Some(String::from("[\"hello\"]")),
+            None,
+            0,
+            Some(vec![Bytes::from_static(b"zero")]),
+        );
+        assert!(matches!(
+            packet.check_attachments(),
+            Err(Error::IllegalAttachmentCount(0, 1))
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_illegal_packet_id() {
         let _sut = PacketId::try_from(42).expect_err("error!");
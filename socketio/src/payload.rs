@@ -2,13 +2,20 @@ use bytes::Bytes;
 
 /// A type which represents a `payload` in the `socket.io` context.
 /// A payload could either be of the type `Payload::Binary`, which holds
-/// data in the [`Bytes`] type that represents the payload or of the type
-/// `Payload::String` which holds a [`std::string::String`]. The enum is
+/// data in the [`Bytes`] type that represents the payload, of the type
+/// `Payload::String` which holds a [`std::string::String`] or of the type
+/// `Payload::Value` which holds a [`serde_json::Value`]. The enum is
 /// used for both representing data that's send and data that's received.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Payload {
+    /// Binary data, sent as a single attachment. Empty bytes are sent as an empty
+    /// attachment rather than being dropped.
     Binary(Bytes),
     String(String),
+    /// Structured data that is serialized exactly once when it's sent, prefer
+    /// this over `Payload::String` for JSON built in code. Received payloads are
+    /// never of this type.
+    Value(serde_json::Value),
 }
 
 impl From<&str> for Payload {
@@ -25,7 +32,7 @@ impl From<String> for Payload {
 
 impl From<serde_json::Value> for Payload {
     fn from(value: serde_json::Value) -> Self {
-        Self::String(value.to_string())
+        Self::Value(value)
     }
 }
 
@@ -63,7 +70,7 @@ mod tests {
         assert_eq!(Payload::String(String::from("foo")), sut);
 
         let sut = Payload::from(json!("foo"));
-        assert_eq!(Payload::String(String::from("\"foo\"")), sut);
+        assert_eq!(Payload::Value(json!("foo")), sut);
 
         let sut = Payload::from(vec![1, 2, 3]);
         assert_eq!(Payload::Binary(Bytes::from_static(&[1, 2, 3])), sut);
@@ -3,7 +3,7 @@ use crate::packet::{Packet, PacketId};
 use bytes::Bytes;
 use rust_engineio::{Client as EngineClient, Packet as EnginePacket, PacketId as EnginePacketId};
 use std::convert::TryFrom;
-use std::sync::{atomic::AtomicBool, Arc};
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
 use std::{fmt::Debug, sync::atomic::Ordering};
 
 use super::{event::Event, payload::Payload};
@@ -13,16 +13,24 @@ use super::{event::Event, payload::Payload};
 pub(crate) struct Socket {
     //TODO: 0.4.0 refactor this
     engine_client: Arc<EngineClient>,
+    // whether the namespace `nsp` is connected, packets of other namespaces
+    // sharing the engine.io connection don't affect it
     connected: Arc<AtomicBool>,
+    nsp: String,
+    // serializes writes so a packet and its attachments are never interleaved
+    // with the ones of a concurrent `send`
+    send_lock: Arc<Mutex<()>>,
 }
 
 impl Socket {
     /// Creates an instance of `Socket`.
 
-    pub(super) fn new(engine_client: EngineClient) -> Result<Self> {
+    pub(super) fn new(engine_client: EngineClient, nsp: &str) -> Result<Self> {
         Ok(Socket {
             engine_client: Arc::new(engine_client),
             connected: Arc::new(AtomicBool::default()),
+            nsp: nsp.to_owned(),
+            send_lock: Arc::new(Mutex::new(())),
         })
     }
 
@@ -56,6 +64,8 @@ impl Socket {
             return Err(Error::IllegalActionBeforeOpen());
         }
 
+        let _send_guard = self.send_lock.lock()?;
+
         // the packet, encoded as an engine.io message packet
         let engine_packet = EnginePacket::new(EnginePacketId::Message, Bytes::from(&packet));
         self.engine_client.emit(engine_packet)?;
@@ -73,7 +83,22 @@ impl Socket {
     /// Emits to certain event with given data. The data needs to be JSON,
     /// otherwise this returns an `InvalidJson` error.
     pub fn emit(&self, nsp: &str, event: Event, data: Payload) -> Result<()> {
-        let socket_packet = self.build_packet_for_payload(data, event, nsp, None)?;
+        let socket_packet = Self::build_packet_for_payload(data, event, nsp, None)?;
+
+        self.send(socket_packet)
+    }
+
+    /// Emits an event without any data attached to it.
+    pub fn emit_event(&self, nsp: &str, event: Event) -> Result<()> {
+        let payload = serde_json::Value::Array(vec![serde_json::Value::String(event.into())]);
+        let socket_packet = Packet::new(
+            PacketId::Event,
+            nsp.to_owned(),
+            Some(payload.to_string()),
+            None,
+            0,
+            None,
+        );
 
         self.send(socket_packet)
     }
@@ -81,11 +106,10 @@ impl Socket {
     /// Returns a packet for a payload, could be used for bot binary and non binary
     /// events and acks. Convenance method.
     #[inline]
-    pub(crate) fn build_packet_for_payload<'a>(
-        &'a self,
+    pub(crate) fn build_packet_for_payload(
         payload: Payload,
         event: Event,
-        nsp: &'a str,
+        nsp: &str,
         id: Option<i32>,
     ) -> Result<Packet> {
         match payload {
@@ -115,6 +139,19 @@ impl Socket {
                     None,
                 ))
             }
+            Payload::Value(value) => {
+                let payload =
+                    serde_json::Value::Array(vec![serde_json::Value::String(event.into()), value]);
+
+                Ok(Packet::new(
+                    PacketId::Event,
+                    nsp.to_owned(),
+                    Some(payload.to_string()),
+                    id,
+                    0,
+                    None,
+                ))
+            }
         }
     }
 
@@ -143,17 +180,22 @@ impl Socket {
     /// Handles the connection/disconnection.
     #[inline]
     fn handle_socketio_packet(&self, socket_packet: &Packet) {
+        if let Some(connected) = Self::connected_after(&self.nsp, socket_packet) {
+            self.connected.store(connected, Ordering::Release);
+        }
+    }
+
+    /// Returns the connection state of the namespace `nsp` after the given packet, or
+    /// `None` if the packet doesn't change it, e.g. as it's meant for another namespace.
+    fn connected_after(nsp: &str, socket_packet: &Packet) -> Option<bool> {
+        if socket_packet.nsp != nsp {
+            return None;
+        }
+
         match socket_packet.packet_type {
-            PacketId::Connect => {
-                self.connected.store(true, Ordering::Release);
-            }
-            PacketId::ConnectError => {
-                self.connected.store(false, Ordering::Release);
-            }
-            PacketId::Disconnect => {
-                self.connected.store(false, Ordering::Release);
-            }
-            _ => (),
+            PacketId::Connect => Some(true),
+            PacketId::ConnectError | PacketId::Disconnect => Some(false),
+            _ => None,
         }
     }
 
@@ -161,8 +203,10 @@ impl Socket {
     fn handle_engineio_packet(&self, packet: EnginePacket) -> Result<Packet> {
         let mut socket_packet = Packet::try_from(&packet.data)?;
 
-        // Only handle attachments if there are any
-        if socket_packet.attachment_count > 0 {
+        // Only handle attachments if there are any. All the declared attachments are
+        // read before they're checked against the placeholders, so a malformed packet
+        // doesn't leave its attachments to be parsed as the next packets.
+        if socket_packet.attachment_count > 0 || !socket_packet.placeholders.is_empty() {
             let mut attachments_left = socket_packet.attachment_count;
             let mut attachments = Vec::new();
             while attachments_left > 0 {
@@ -186,7 +230,7 @@ impl Socket {
                     }
                 }
             }
-            socket_packet.attachments = Some(attachments);
+            socket_packet.bind_attachments(attachments)?;
         }
 
         Ok(socket_packet)
@@ -196,3 +240,65 @@ impl Socket {
         Ok(self.engine_client.is_connected()?)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_packet_for_value_payload() -> Result<()> {
+        let value = json!({"nested": {"list": [1, "two", null], "quote": "\"x\""}});
+        let packet = Socket::build_packet_for_payload(
+            Payload::Value(value.clone()),
+            "test".into(),
+            "/",
+            None,
+        )?;
+
+        let decoded = Packet::try_from(&Bytes::from(&packet))?;
+        assert_eq!(decoded.packet_type, PacketId::Event);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&decoded.data.unwrap())?,
+            json!(["test", value])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_packet_for_empty_binary_payload() -> Result<()> {
+        let packet = Socket::build_packet_for_payload(
+            Payload::Binary(Bytes::new()),
+            "test".into(),
+            "/",
+            None,
+        )?;
+
+        assert_eq!(packet.packet_type, PacketId::BinaryEvent);
+        assert_eq!(packet.attachment_count, 1);
+        assert_eq!(packet.attachments, Some(vec![Bytes::new()]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_namespaced_disconnect() -> Result<()> {
+        let disconnect = Packet::try_from(&Bytes::from_static(b"1/admin,"))?;
+        assert_eq!(disconnect.nsp, "/admin");
+
+        // only the namespace that left is disconnected
+        assert_eq!(Socket::connected_after("/admin", &disconnect), Some(false));
+        assert_eq!(Socket::connected_after("/", &disconnect), None);
+
+        let disconnect = Packet::try_from(&Bytes::from_static(b"1"))?;
+        assert_eq!(Socket::connected_after("/", &disconnect), Some(false));
+        assert_eq!(Socket::connected_after("/admin", &disconnect), None);
+
+        let connect = Packet::try_from(&Bytes::from_static(b"0/admin,{\"sid\":\"1\"}"))?;
+        assert_eq!(Socket::connected_after("/admin", &connect), Some(true));
+        assert_eq!(Socket::connected_after("/", &connect), None);
+
+        Ok(())
+    }
+}